@@ -0,0 +1,145 @@
+use std::sync::Arc;
+
+use log::error;
+use serenity::all::{
+	AutocompleteChoice, CommandInteraction, CreateAutocompleteResponse, CreateInteractionResponse,
+	Http
+};
+
+use crate::{
+	config::client_config::GuildConfig,
+	model::{
+		level_request::SearchLevelRequests, requestx_api::level_request_data::LevelRequestData
+	},
+	service::services::Services,
+	util::permission::{get_permission, get_required_permission}
+};
+
+// Discord rejects autocomplete responses with more than 25 choices or choice
+// names longer than 100 characters
+const MAX_AUTOCOMPLETE_CHOICES: u64 = 25;
+const MAX_CHOICE_NAME_LENGTH: usize = 100;
+
+pub async fn run_autocomplete(
+	http: &Arc<Http>,
+	services: &Services,
+	autocomplete: &CommandInteraction,
+	guild_config: &GuildConfig
+) {
+	let choices = match autocomplete.data.autocomplete() {
+		Some(focused_option) if focused_option.name == "level-id" => {
+			let permission = get_permission(
+				&autocomplete.user,
+				autocomplete.member.as_deref(),
+				guild_config
+			);
+			if permission.is_some_and(|permission| {
+				permission >= get_required_permission(&autocomplete.data.name)
+			}) {
+				get_level_id_choices(services, focused_option.value).await
+			} else {
+				vec![]
+			}
+		}
+		_ => vec![]
+	};
+
+	let builder = CreateInteractionResponse::Autocomplete(
+		CreateAutocompleteResponse::new().set_choices(choices)
+	);
+	if let Err(err) = autocomplete.create_response(http, builder).await {
+		error!("Cannot respond to autocomplete: {err}");
+	}
+}
+
+async fn get_level_id_choices(services: &Services, search: &str) -> Vec<AutocompleteChoice> {
+	let search_level_requests = SearchLevelRequests {
+		page: 0,
+		page_size: MAX_AUTOCOMPLETE_CHOICES,
+		search: search.trim().to_string()
+	};
+
+	match services
+		.level_request_service
+		.search_level_requests(search_level_requests)
+		.await
+	{
+		Ok(level_request_queue) => level_request_queue
+			.level_requests
+			.iter()
+			.map(|level_request| {
+				AutocompleteChoice::new(
+					get_level_choice_name(level_request),
+					level_request.level_id
+				)
+			})
+			.collect(),
+		Err(error) => {
+			error!("Unable to search level requests: {}", error);
+			vec![]
+		}
+	}
+}
+
+fn get_level_choice_name(level_request: &LevelRequestData) -> String {
+	let level_id = format!(" ({})", level_request.level_id);
+	let mut level_name = format!(
+		"\"{}\" by {}",
+		level_request.level_name, level_request.level_author
+	);
+	if level_name.chars().count() + level_id.len() > MAX_CHOICE_NAME_LENGTH {
+		level_name = level_name
+			.chars()
+			.take(MAX_CHOICE_NAME_LENGTH - level_id.len() - 3)
+			.collect::<String>()
+			+ "...";
+	}
+
+	level_name + &level_id
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		commands::autocomplete::{get_level_choice_name, MAX_CHOICE_NAME_LENGTH},
+		model::{
+			request_score::{LevelLength, RequestRating},
+			requestx_api::level_request_data::LevelRequestData
+		}
+	};
+
+	fn test_level_request(level_name: &str) -> LevelRequestData {
+		LevelRequestData {
+			level_id: 97624039,
+			discord_id: 164072941645070336,
+			discord_message_id: None,
+			discord_thread_id: None,
+			discord_channel_id: None,
+			level_name: level_name.to_string(),
+			level_author: "Some Author".to_string(),
+			request_score: RequestRating::Four,
+			level_length: LevelLength::Long,
+			youtube_video_link: "https://youtu.be/dQw4w9WgXcQ".to_string(),
+			has_requested_feedback: false,
+			notify: false,
+			suggested_score: None,
+			suggested_rating: None
+		}
+	}
+
+	#[test]
+	fn level_choice_name_should_include_name_author_and_id() {
+		assert_eq!(
+			get_level_choice_name(&test_level_request("Some Level")),
+			"\"Some Level\" by Some Author (97624039)"
+		);
+	}
+
+	#[test]
+	fn level_choice_name_should_truncate_long_names_but_keep_id() {
+		let choice_name = get_level_choice_name(&test_level_request(&"A".repeat(150)));
+
+		assert_eq!(choice_name.chars().count(), MAX_CHOICE_NAME_LENGTH);
+		assert!(choice_name.ends_with("... (97624039)"));
+	}
+}
@@ -1,4 +1,9 @@
+pub mod autocomplete;
+pub mod queue;
+pub mod request_actions;
 pub mod request_level;
+pub mod request_manage;
+pub mod request_status;
 pub mod review;
 pub mod reviewer;
 pub mod send_level;
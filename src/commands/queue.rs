@@ -0,0 +1,369 @@
+use std::{str::FromStr, sync::Arc};
+
+use log::error;
+use serenity::all::{
+	ButtonStyle, ChannelId, CommandInteraction, ComponentInteraction, CreateActionRow,
+	CreateButton, CreateCommand, CreateInteractionResponse, EditInteractionResponse, GuildId, Http,
+	MessageBuilder, MessageId
+};
+
+use crate::{
+	commands::request_level::REQUEST_RATING_CHOICES,
+	config::client_config::GuildConfig,
+	model::{
+		error::{level_request_error::LevelRequestError, requestx_error::RequestXError},
+		level_request::GetLevelRequestQueue,
+		request_score::{LevelLength, RequestRating}
+	},
+	service::services::Services,
+	util::{
+		command_options::{command_args, CommandOption},
+		discord::DeferredResponse
+	}
+};
+
+pub const QUEUE_CUSTOM_ID_PREFIX: &str = "queue";
+const QUEUE_PAGE_SIZE: u64 = 10;
+
+command_args! {
+	pub struct QueueArgs {
+		request_rating: Option<RequestRating> = CommandOption::choice(
+			"request-rating",
+			"Only list requests for this amount of Stars/Moons.",
+			&REQUEST_RATING_CHOICES
+		)
+		.optional(),
+		level_length: Option<LevelLength> = CommandOption::choice(
+			"level-length",
+			"Only list requests for levels of this length.",
+			&[
+				("Tiny", "Tiny"),
+				("Short", "Short"),
+				("Medium", "Medium"),
+				("Long", "Long"),
+				("XL", "XL"),
+				("Platformer", "Platformer")
+			]
+		)
+		.optional(),
+		request_feedback: Option<bool> = CommandOption::boolean(
+			"request-feedback",
+			"Only list requests that have or have not requested feedback."
+		)
+		.optional(),
+		max_age_hours: Option<u64> = CommandOption::unsigned_integer(
+			"max-age-hours",
+			"Only list requests made within this many hours."
+		)
+		.min_int_value(1)
+		.optional()
+	}
+}
+
+pub fn register_queue() -> CreateCommand {
+	CreateCommand::new("queue")
+		.description("Lists the pending level requests")
+		.set_options(QueueArgs::create_options())
+}
+
+pub async fn run_queue(
+	http: &Arc<Http>,
+	services: &Services,
+	command: &CommandInteraction,
+	guild_config: &GuildConfig
+) {
+	let response = DeferredResponse::defer_command(http, command).await;
+	let args = match QueueArgs::parse(&command.data.options) {
+		Ok(args) => args,
+		Err(error) => {
+			error!("Invalid queue options: {}", error);
+			response.edit(&error.to_string()).await;
+			return;
+		}
+	};
+	let get_level_request_queue = GetLevelRequestQueue {
+		page: 0,
+		page_size: QUEUE_PAGE_SIZE,
+		request_rating: args.request_rating,
+		level_length: args.level_length,
+		has_requested_feedback: args.request_feedback,
+		max_age_hours: args.max_age_hours
+	};
+
+	match build_queue_message(services, guild_config, get_level_request_queue).await {
+		Ok(queue_message) => {
+			response
+				.edit_with(
+					EditInteractionResponse::new()
+						.content(queue_message.content)
+						.components(queue_message.components)
+				)
+				.await
+		}
+		Err(error) => {
+			error!("Unable to get level request queue: {}", error);
+			response.edit(&queue_error_message(&error)).await;
+		}
+	}
+}
+
+pub async fn run_queue_page(
+	http: &Arc<Http>,
+	services: &Services,
+	component: &ComponentInteraction,
+	guild_config: &GuildConfig
+) {
+	let get_level_request_queue = match parse_custom_id(&component.data.custom_id) {
+		Some(get_level_request_queue) => get_level_request_queue,
+		None => {
+			error!("Invalid queue custom ID: {}", component.data.custom_id);
+			if let Err(err) = component
+				.create_response(http, CreateInteractionResponse::Acknowledge)
+				.await
+			{
+				error!("Cannot respond to queue component: {err}");
+			}
+			return;
+		}
+	};
+
+	let response = DeferredResponse::defer_component_update(http, component).await;
+	match build_queue_message(services, guild_config, get_level_request_queue).await {
+		Ok(queue_message) => {
+			response
+				.edit_with(
+					EditInteractionResponse::new()
+						.content(queue_message.content)
+						.components(queue_message.components)
+				)
+				.await
+		}
+		Err(error) => {
+			error!("Unable to get level request queue: {}", error);
+			response.follow_up(&queue_error_message(&error)).await
+		}
+	}
+}
+
+struct QueueMessage {
+	content: String,
+	components: Vec<CreateActionRow>
+}
+
+async fn build_queue_message(
+	services: &Services,
+	guild_config: &GuildConfig,
+	get_level_request_queue: GetLevelRequestQueue
+) -> Result<QueueMessage, LevelRequestError> {
+	let level_request_queue = services
+		.level_request_service
+		.get_level_request_queue(get_level_request_queue)
+		.await?;
+
+	let mut queue_message = MessageBuilder::new();
+	queue_message.push_bold_line(format!(
+		"Level request queue (Page {}/{})",
+		level_request_queue.page + 1,
+		level_request_queue.total_pages.max(1)
+	));
+	if level_request_queue.level_requests.is_empty() {
+		queue_message.push_line("There are no pending level requests matching the filters.");
+	}
+	for (index, level_request) in level_request_queue.level_requests.iter().enumerate() {
+		queue_message.push(format!(
+			"{}. \"{}\" by {} ({}) - {}, {}",
+			level_request_queue.page * QUEUE_PAGE_SIZE + index as u64 + 1,
+			level_request.level_name,
+			level_request.level_author,
+			level_request.level_id,
+			level_request.request_score,
+			level_request.level_length
+		));
+		if level_request.has_requested_feedback {
+			queue_message.push(" - Feedback requested");
+		}
+		if let Some(message_id) = level_request.discord_message_id {
+			queue_message.push(format!(
+				" {}",
+				MessageId::new(message_id).link(
+					ChannelId::new(guild_config.get_level_request_channel_id(level_request)),
+					Some(GuildId::new(guild_config.discord_guild_id))
+				)
+			));
+		}
+		queue_message.push_line("");
+	}
+
+	let previous_page = GetLevelRequestQueue {
+		page: level_request_queue.page.saturating_sub(1),
+		..get_level_request_queue
+	};
+	let next_page = GetLevelRequestQueue {
+		page: level_request_queue.page + 1,
+		..get_level_request_queue
+	};
+
+	Ok(QueueMessage {
+		content: queue_message.build(),
+		components: vec![CreateActionRow::Buttons(vec![
+			CreateButton::new(to_custom_id(&previous_page))
+				.label("Previous")
+				.style(ButtonStyle::Secondary)
+				.disabled(level_request_queue.page == 0),
+			CreateButton::new(to_custom_id(&next_page))
+				.label("Next")
+				.style(ButtonStyle::Secondary)
+				.disabled(next_page.page >= level_request_queue.total_pages),
+		])]
+	})
+}
+
+fn queue_error_message(error: &LevelRequestError) -> String {
+	match error {
+		LevelRequestError::RequestX(requestx_error @ RequestXError::Unavailable) => {
+			requestx_error.user_message()
+		}
+		_ => "There was an error getting the queue.".to_string()
+	}
+}
+
+// Custom IDs are limited to 100 characters, so the filters are packed as
+// `queue:page:rating:length:feedback:age` with empty segments for unset filters
+fn to_custom_id(get_level_request_queue: &GetLevelRequestQueue) -> String {
+	let request_rating = get_level_request_queue
+		.request_rating
+		.and_then(|request_rating| serde_json::to_value(request_rating).ok())
+		.and_then(|request_rating| request_rating.as_str().map(str::to_string))
+		.unwrap_or_default();
+	let level_length = get_level_request_queue
+		.level_length
+		.and_then(|level_length| serde_json::to_value(level_length).ok())
+		.and_then(|level_length| level_length.as_str().map(str::to_string))
+		.unwrap_or_default();
+
+	format!(
+		"{}:{}:{}:{}:{}:{}",
+		QUEUE_CUSTOM_ID_PREFIX,
+		get_level_request_queue.page,
+		request_rating,
+		level_length,
+		get_level_request_queue
+			.has_requested_feedback
+			.map_or(String::new(), |has_requested_feedback| {
+				has_requested_feedback.to_string()
+			}),
+		get_level_request_queue
+			.max_age_hours
+			.map_or(String::new(), |max_age_hours| max_age_hours.to_string())
+	)
+}
+
+fn parse_custom_id(custom_id: &str) -> Option<GetLevelRequestQueue> {
+	let segments: Vec<&str> = custom_id.split(':').collect();
+	if segments.len() != 6 || segments[0] != QUEUE_CUSTOM_ID_PREFIX {
+		return None;
+	}
+
+	Some(GetLevelRequestQueue {
+		page: segments[1].parse().ok()?,
+		page_size: QUEUE_PAGE_SIZE,
+		request_rating: RequestRating::from_str(segments[2]).ok(),
+		level_length: LevelLength::from_str(segments[3]).ok(),
+		has_requested_feedback: segments[4].parse().ok(),
+		max_age_hours: segments[5].parse().ok()
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+
+	use serde_json::json;
+
+	use crate::{
+		commands::queue::{parse_custom_id, run_queue_page, to_custom_id, QUEUE_PAGE_SIZE},
+		model::{
+			level_request::{GetLevelRequestQueue, LevelRequest},
+			request_score::{LevelLength, RequestRating},
+			requestx_api::{
+				in_memory_requestx_api::InMemoryRequestXApi, requestx_api_trait::RequestXApi
+			}
+		},
+		service::services::Services,
+		test_harness::{
+			component_interaction, init_mock_server, mock_acknowledged_component,
+			mock_edited_response, test_guild_config, test_http, RecordingDiscordGateway,
+			TEST_USER_ID
+		}
+	};
+
+	#[test]
+	fn custom_id_should_round_trip_filters() {
+		let get_level_request_queue = GetLevelRequestQueue {
+			page: 3,
+			page_size: QUEUE_PAGE_SIZE,
+			request_rating: Some(RequestRating::Ten),
+			level_length: Some(LevelLength::ExtraLong),
+			has_requested_feedback: Some(true),
+			max_age_hours: Some(48)
+		};
+		let custom_id = to_custom_id(&get_level_request_queue);
+		let parsed = parse_custom_id(&custom_id).unwrap();
+
+		assert!(custom_id.len() <= 100);
+		assert_eq!(parsed.page, 3);
+		assert_eq!(parsed.request_rating, Some(RequestRating::Ten));
+		assert_eq!(parsed.level_length, Some(LevelLength::ExtraLong));
+		assert_eq!(parsed.has_requested_feedback, Some(true));
+		assert_eq!(parsed.max_age_hours, Some(48));
+	}
+
+	#[test]
+	fn custom_id_should_round_trip_without_filters() {
+		let get_level_request_queue = GetLevelRequestQueue {
+			page: 0,
+			page_size: QUEUE_PAGE_SIZE,
+			request_rating: None,
+			level_length: None,
+			has_requested_feedback: None,
+			max_age_hours: None
+		};
+		let parsed = parse_custom_id(&to_custom_id(&get_level_request_queue)).unwrap();
+
+		assert_eq!(parsed.page, 0);
+		assert_eq!(parsed.request_rating, None);
+		assert_eq!(parsed.level_length, None);
+		assert_eq!(parsed.has_requested_feedback, None);
+		assert_eq!(parsed.max_age_hours, None);
+	}
+
+	#[tokio::test]
+	async fn queue_page_should_acknowledge_before_loading_the_page() {
+		let server = init_mock_server().await;
+		let requestx_api = Arc::new(InMemoryRequestXApi::default());
+		requestx_api
+			.make_requestx_api_level_request(LevelRequest {
+				discord_user_id: TEST_USER_ID,
+				level_id: 97624039,
+				request_score: RequestRating::Four,
+				youtube_video_link: "https://youtu.be/dQw4w9WgXcQ".to_string(),
+				has_requested_feedback: false,
+				notify: false
+			})
+			.await
+			.unwrap();
+		let acknowledged_mock = mock_acknowledged_component(&server).await;
+		let page_response_mock = mock_edited_response(&server, "(97624039)").await;
+
+		run_queue_page(
+			&test_http(&server),
+			&Services::new(requestx_api, Arc::new(RecordingDiscordGateway::default())),
+			&component_interaction("queue:0::::", json!([]), json!([])),
+			test_guild_config()
+		)
+		.await;
+
+		acknowledged_mock.assert_async().await;
+		page_response_mock.assert_async().await;
+	}
+}
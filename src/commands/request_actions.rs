@@ -0,0 +1,512 @@
+use std::{str::FromStr, sync::Arc};
+
+use log::{error, warn};
+use serenity::all::{
+	ButtonStyle, ComponentInteraction, ComponentInteractionDataKind, CreateActionRow, CreateButton,
+	CreateInteractionResponse, CreateInteractionResponseMessage, CreateSelectMenu,
+	CreateSelectMenuKind, CreateSelectMenuOption, EditInteractionResponse, Http
+};
+
+use crate::{
+	commands::{
+		request_level::REQUEST_RATING_CHOICES,
+		review::create_review_modal,
+		send_level::{send_level, SUGGESTED_RATING_CHOICES}
+	},
+	config::client_config::GuildConfig,
+	model::{
+		discord_user::DiscordUser,
+		moderator::{Moderator, SuggestedRating, SuggestedScore},
+		permission::Permission
+	},
+	service::services::Services,
+	util::{
+		discord::{invoke_component_ephermal, DeferredResponse},
+		permission::authorize_component
+	}
+};
+
+pub const REQUEST_ACTION_CUSTOM_ID_PREFIX: &str = "request";
+
+pub fn create_request_action_buttons(level_id: u64) -> Vec<CreateActionRow> {
+	vec![CreateActionRow::Buttons(vec![
+		CreateButton::new(format!(
+			"{}:send:{}",
+			REQUEST_ACTION_CUSTOM_ID_PREFIX, level_id
+		))
+		.label("Send")
+		.style(ButtonStyle::Success),
+		CreateButton::new(format!(
+			"{}:no-send:{}",
+			REQUEST_ACTION_CUSTOM_ID_PREFIX, level_id
+		))
+		.label("No send")
+		.style(ButtonStyle::Danger),
+		CreateButton::new(format!(
+			"{}:review:{}",
+			REQUEST_ACTION_CUSTOM_ID_PREFIX, level_id
+		))
+		.label("Review")
+		.style(ButtonStyle::Secondary),
+	])]
+}
+
+// Custom IDs follow `request:action:level_id[:suggested_score]` so every step
+// of the send flow carries the level it was started from
+pub async fn run_request_action(
+	http: &Arc<Http>,
+	services: &Services,
+	component: &ComponentInteraction,
+	guild_config: &GuildConfig
+) {
+	let segments: Vec<&str> = component.data.custom_id.split(':').collect();
+	let level_id = match segments
+		.get(2)
+		.and_then(|level_id| level_id.parse::<u64>().ok())
+	{
+		Some(level_id) => level_id,
+		None => {
+			error!(
+				"Invalid request action custom ID: {}",
+				component.data.custom_id
+			);
+			invoke_component_ephermal("Unable to handle request action", http, component).await;
+			return;
+		}
+	};
+	let required_permission = match segments[1] {
+		"review" => Permission::Reviewer,
+		_ => Permission::Moderator
+	};
+	if !authorize_component(http, component, guild_config, required_permission).await {
+		return;
+	}
+
+	match segments[1] {
+		"send" => {
+			respond(
+				http,
+				component,
+				CreateInteractionResponse::Message(
+					CreateInteractionResponseMessage::new()
+						.ephemeral(true)
+						.content(format!("Select the suggested score for level {}", level_id))
+						.components(vec![create_suggested_score_menu(level_id)])
+				)
+			)
+			.await
+		}
+		"send-score" => match (selected_value(component), segments.len()) {
+			(Some(suggested_score), 3) => {
+				respond(
+					http,
+					component,
+					CreateInteractionResponse::UpdateMessage(
+						CreateInteractionResponseMessage::new()
+							.content(format!(
+								"Select the suggested rating for level {}",
+								level_id
+							))
+							.components(vec![create_suggested_rating_menu(
+								level_id,
+								&suggested_score
+							)])
+					)
+				)
+				.await
+			}
+			_ => invoke_component_ephermal("Unable to handle request action", http, component).await
+		},
+		"send-rating" => {
+			let suggested_score = segments
+				.get(3)
+				.and_then(|suggested_score| SuggestedScore::from_str(suggested_score).ok());
+			let suggested_rating = selected_value(component)
+				.and_then(|suggested_rating| SuggestedRating::from_str(&suggested_rating).ok());
+			if let (Some(suggested_score), Some(suggested_rating)) =
+				(suggested_score, suggested_rating)
+			{
+				let send_level_request = Moderator {
+					level_id,
+					suggested_score,
+					suggested_rating
+				};
+				let response = DeferredResponse::defer_component_update(http, component).await;
+				let content = send_level(
+					services,
+					&DiscordUser::from(&component.user),
+					guild_config,
+					send_level_request
+				)
+				.await;
+				response
+					.edit_with(
+						EditInteractionResponse::new()
+							.content(content)
+							.components(vec![])
+					)
+					.await
+			} else {
+				invoke_component_ephermal("Unable to handle request action", http, component).await
+			}
+		}
+		"no-send" => {
+			respond(
+				http,
+				component,
+				CreateInteractionResponse::Message(
+					CreateInteractionResponseMessage::new()
+						.ephemeral(true)
+						.content(format!(
+							"Level {} will not be sent, this cannot be undone.",
+							level_id
+						))
+						.components(vec![create_no_send_confirmation_buttons(level_id)])
+				)
+			)
+			.await
+		}
+		"no-send-cancel" => {
+			respond(
+				http,
+				component,
+				CreateInteractionResponse::UpdateMessage(
+					CreateInteractionResponseMessage::new()
+						.content(format!("Level {} has been left in the queue.", level_id))
+						.components(vec![])
+				)
+			)
+			.await
+		}
+		"no-send-confirm" => {
+			let send_level_request = Moderator {
+				level_id,
+				suggested_score: SuggestedScore::NoRate,
+				suggested_rating: SuggestedRating::Rate
+			};
+			let response = DeferredResponse::defer_component_update(http, component).await;
+			let content = send_level(
+				services,
+				&DiscordUser::from(&component.user),
+				guild_config,
+				send_level_request
+			)
+			.await;
+			response
+				.edit_with(
+					EditInteractionResponse::new()
+						.content(content)
+						.components(vec![])
+				)
+				.await
+		}
+		"review" => {
+			respond(
+				http,
+				component,
+				CreateInteractionResponse::Modal(
+					create_review_modal(services, level_id, &component.user).await
+				)
+			)
+			.await
+		}
+		_ => {
+			warn!("Unknown request action: {}", component.data.custom_id);
+			invoke_component_ephermal("Unable to handle request action", http, component).await
+		}
+	}
+}
+
+fn create_no_send_confirmation_buttons(level_id: u64) -> CreateActionRow {
+	CreateActionRow::Buttons(vec![
+		CreateButton::new(format!(
+			"{}:no-send-confirm:{}",
+			REQUEST_ACTION_CUSTOM_ID_PREFIX, level_id
+		))
+		.label("Confirm no send")
+		.style(ButtonStyle::Danger),
+		CreateButton::new(format!(
+			"{}:no-send-cancel:{}",
+			REQUEST_ACTION_CUSTOM_ID_PREFIX, level_id
+		))
+		.label("Cancel")
+		.style(ButtonStyle::Secondary),
+	])
+}
+
+fn create_suggested_score_menu(level_id: u64) -> CreateActionRow {
+	let options = REQUEST_RATING_CHOICES
+		.into_iter()
+		.map(|(label, value)| CreateSelectMenuOption::new(label, value))
+		.collect();
+
+	CreateActionRow::SelectMenu(
+		CreateSelectMenu::new(
+			format!(
+				"{}:send-score:{}",
+				REQUEST_ACTION_CUSTOM_ID_PREFIX, level_id
+			),
+			CreateSelectMenuKind::String { options }
+		)
+		.placeholder("Suggested score")
+	)
+}
+
+fn create_suggested_rating_menu(level_id: u64, suggested_score: &str) -> CreateActionRow {
+	let options = SUGGESTED_RATING_CHOICES
+		.into_iter()
+		.map(|(label, value)| CreateSelectMenuOption::new(label, value))
+		.collect();
+
+	CreateActionRow::SelectMenu(
+		CreateSelectMenu::new(
+			format!(
+				"{}:send-rating:{}:{}",
+				REQUEST_ACTION_CUSTOM_ID_PREFIX, level_id, suggested_score
+			),
+			CreateSelectMenuKind::String { options }
+		)
+		.placeholder("Suggested rating")
+	)
+}
+
+fn selected_value(component: &ComponentInteraction) -> Option<String> {
+	match &component.data.kind {
+		ComponentInteractionDataKind::StringSelect { values } => values.first().cloned(),
+		_ => None
+	}
+}
+
+async fn respond(
+	http: &Arc<Http>,
+	component: &ComponentInteraction,
+	builder: CreateInteractionResponse
+) {
+	if let Err(err) = component.create_response(http, builder).await {
+		error!("Cannot respond to request action: {err}");
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+
+	use httpmock::Method::POST;
+	use serde_json::json;
+
+	use crate::{
+		commands::request_actions::run_request_action,
+		config::requestx_api_config::REQUESTX_API_CONFIG,
+		model::{
+			level_request::{GetLevelRequest, LevelRequest, UpdateLevelRequestMessageId},
+			moderator::{SuggestedRating, SuggestedScore},
+			request_score::RequestRating,
+			requestx_api::{
+				in_memory_requestx_api::InMemoryRequestXApi, requestx_api_trait::RequestXApi
+			}
+		},
+		service::services::Services,
+		test_harness::{
+			component_interaction, init_mock_server, mock_acknowledged_component,
+			mock_edited_response, mock_interaction_response, test_guild_config, test_http,
+			test_services, DiscordGatewayCall, RecordingDiscordGateway, TEST_MODERATOR_ROLE_ID,
+			TEST_REVIEWER_ROLE_ID, TEST_USER_ID
+		}
+	};
+
+	async fn request_level(requestx_api: &InMemoryRequestXApi) {
+		requestx_api
+			.make_requestx_api_level_request(LevelRequest {
+				discord_user_id: TEST_USER_ID,
+				level_id: 97624039,
+				request_score: RequestRating::Four,
+				youtube_video_link: "https://youtu.be/dQw4w9WgXcQ".to_string(),
+				has_requested_feedback: true,
+				notify: false
+			})
+			.await
+			.unwrap();
+		requestx_api
+			.update_request_message_id(UpdateLevelRequestMessageId {
+				level_id: 97624039,
+				discord_message_id: 300,
+				discord_channel_id: test_guild_config().discord_request_channel_id
+			})
+			.await
+			.unwrap();
+	}
+
+	#[tokio::test]
+	async fn no_send_should_ask_for_confirmation_before_sending() {
+		let server = init_mock_server().await;
+		let send_level_mock = server
+			.mock_async(|when, then| {
+				when.method(POST)
+					.path(&*REQUESTX_API_CONFIG.paths.send_level);
+				then.status(200);
+			})
+			.await;
+		let confirmation_mock =
+			mock_interaction_response(&server, "request:no-send-confirm:97624039").await;
+
+		run_request_action(
+			&test_http(&server),
+			&test_services(&server),
+			&component_interaction(
+				"request:no-send:97624039",
+				json!([]),
+				json!([TEST_MODERATOR_ROLE_ID])
+			),
+			test_guild_config()
+		)
+		.await;
+
+		confirmation_mock.assert_async().await;
+		assert_eq!(send_level_mock.hits_async().await, 0);
+	}
+
+	#[tokio::test]
+	async fn send_rating_should_acknowledge_before_sending_the_level() {
+		let server = init_mock_server().await;
+		let acknowledged_mock = mock_acknowledged_component(&server).await;
+		let edited_response_mock =
+			mock_edited_response(&server, "Level request does not exist.").await;
+		let services = Services::new(
+			Arc::new(InMemoryRequestXApi::default()),
+			Arc::new(RecordingDiscordGateway::default())
+		);
+
+		run_request_action(
+			&test_http(&server),
+			&services,
+			&component_interaction(
+				"request:send-rating:97624039:Four",
+				json!(["Feature"]),
+				json!([TEST_MODERATOR_ROLE_ID])
+			),
+			test_guild_config()
+		)
+		.await;
+
+		acknowledged_mock.assert_async().await;
+		edited_response_mock.assert_async().await;
+	}
+
+	#[tokio::test]
+	async fn send_flow_should_suggest_score_then_rating_then_send_the_level() {
+		let server = init_mock_server().await;
+		let requestx_api = Arc::new(InMemoryRequestXApi::default());
+		request_level(&requestx_api).await;
+		let discord_gateway = Arc::new(RecordingDiscordGateway::default());
+		let services = Services::new(requestx_api.clone(), discord_gateway.clone());
+		let score_menu_mock =
+			mock_interaction_response(&server, "request:send-score:97624039").await;
+		let rating_menu_mock =
+			mock_interaction_response(&server, "request:send-rating:97624039:Four").await;
+		let acknowledged_mock = mock_acknowledged_component(&server).await;
+		let sent_response_mock = mock_edited_response(&server, "Level has been sent!").await;
+
+		for (custom_id, values) in [
+			("request:send:97624039", json!([])),
+			("request:send-score:97624039", json!(["Four"])),
+			("request:send-rating:97624039:Four", json!(["Feature"]))
+		] {
+			run_request_action(
+				&test_http(&server),
+				&services,
+				&component_interaction(custom_id, values, json!([TEST_MODERATOR_ROLE_ID])),
+				test_guild_config()
+			)
+			.await;
+		}
+
+		score_menu_mock.assert_async().await;
+		rating_menu_mock.assert_async().await;
+		acknowledged_mock.assert_async().await;
+		sent_response_mock.assert_async().await;
+		let level_request = requestx_api
+			.get_level_request(GetLevelRequest { level_id: 97624039 })
+			.await
+			.unwrap()
+			.unwrap();
+		assert_eq!(level_request.suggested_score, Some(SuggestedScore::Four));
+		assert_eq!(
+			level_request.suggested_rating,
+			Some(SuggestedRating::Feature)
+		);
+		assert_eq!(
+			*discord_gateway.calls.lock().unwrap(),
+			vec![
+				DiscordGatewayCall::CreateThread {
+					channel_id: test_guild_config().discord_request_channel_id,
+					message_id: 300,
+					thread_id: 1000
+				},
+				DiscordGatewayCall::PostMessage {
+					channel_id: 1000,
+					message_id: 1001
+				}
+			]
+		);
+	}
+
+	#[tokio::test]
+	async fn no_send_confirm_should_conclude_the_request_without_a_rating() {
+		let server = init_mock_server().await;
+		let requestx_api = Arc::new(InMemoryRequestXApi::default());
+		request_level(&requestx_api).await;
+		let acknowledged_mock = mock_acknowledged_component(&server).await;
+		let concluded_response_mock =
+			mock_edited_response(&server, "Level marked as not sent.").await;
+
+		run_request_action(
+			&test_http(&server),
+			&Services::new(
+				requestx_api.clone(),
+				Arc::new(RecordingDiscordGateway::default())
+			),
+			&component_interaction(
+				"request:no-send-confirm:97624039",
+				json!([]),
+				json!([TEST_MODERATOR_ROLE_ID])
+			),
+			test_guild_config()
+		)
+		.await;
+
+		acknowledged_mock.assert_async().await;
+		concluded_response_mock.assert_async().await;
+		assert_eq!(
+			requestx_api
+				.get_level_request(GetLevelRequest { level_id: 97624039 })
+				.await
+				.unwrap()
+				.unwrap()
+				.suggested_score,
+			Some(SuggestedScore::NoRate)
+		);
+	}
+
+	#[tokio::test]
+	async fn review_button_should_open_the_review_modal() {
+		let server = init_mock_server().await;
+		let requestx_api = Arc::new(InMemoryRequestXApi::default());
+		request_level(&requestx_api).await;
+		let modal_response_mock =
+			mock_interaction_response(&server, r#""custom_id":"review:97624039""#).await;
+
+		run_request_action(
+			&test_http(&server),
+			&Services::new(requestx_api, Arc::new(RecordingDiscordGateway::default())),
+			&component_interaction(
+				"request:review:97624039",
+				json!([]),
+				json!([TEST_REVIEWER_ROLE_ID])
+			),
+			test_guild_config()
+		)
+		.await;
+
+		modal_response_mock.assert_async().await;
+	}
+}
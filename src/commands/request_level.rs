@@ -1,200 +1,279 @@
-use std::str::FromStr;
+use std::sync::Arc;
 
 use log::error;
-use serenity::{
-	all::{ChannelId, CommandInteraction, CommandOptionType, MessageBuilder},
-	builder::{CreateCommand, CreateCommandOption},
-	prelude::Context
-};
+use serenity::all::{CommandInteraction, CreateCommand, Http};
 
 use crate::{
+	commands::request_actions::create_request_action_buttons,
+	config::client_config::GuildConfig,
 	model::{
-		error::level_request_error::LevelRequestError,
-		level_request::{LevelRequest, UpdateLevelRequestMessageId},
+		audit_event::AuditEvent,
+		error::{command_option_error::CommandOptionError, level_request_error::LevelRequestError},
+		level_request::LevelRequest,
 		request_score::RequestRating
 	},
-	service::level_request_service::LevelRequestService,
-	util::discord::{invoke_ephermal, log_to_discord}
+	service::services::Services,
+	util::{
+		audit::audit,
+		command_options::{command_args, CommandOption},
+		discord::DeferredResponse,
+		render::render_level_request
+	}
 };
 
+pub const REQUEST_RATING_CHOICES: [(&str, &str); 10] = [
+	("Auto, 1 Star/Moon", "One"),
+	("Easy, 2 Stars/Moons", "Two"),
+	("Normal, 3 Stars/Moons", "Three"),
+	("Hard, 4 Stars/Moons", "Four"),
+	("Hard, 5 Stars/Moons", "Five"),
+	("Harder, 6 Stars/Moons", "Six"),
+	("Harder, 7 Stars/Moons", "Seven"),
+	("Insane, 8 Stars/Moons", "Eight"),
+	("Insane, 9 Stars/Moons", "Nine"),
+	("Demon, 10 Stars/Moons", "Ten")
+];
+
+command_args! {
+	pub struct RequestLevelArgs {
+		level_id: u64 = CommandOption::unsigned_integer(
+			"level-id",
+			"The ID of the level to request."
+		),
+		request_rating: RequestRating = CommandOption::choice(
+			"request-rating",
+			"The amount of Stars/Moons requested.",
+			&REQUEST_RATING_CHOICES
+		),
+		video_link: String = CommandOption::string(
+			"video-link",
+			"A link to the video showcasing the requested level."
+		),
+		request_feedback: bool = CommandOption::boolean(
+			"request-feedback",
+			"Request for reviewers to potentially review your request."
+		),
+		notify: bool = CommandOption::boolean(
+			"notify",
+			"Notify when a review has been made or if the level has been sent."
+		)
+	}
+}
+
 pub fn register() -> CreateCommand {
 	CreateCommand::new("request-level")
 		.description("Request a level to Ryder")
-		.add_option(
-			CreateCommandOption::new(
-				CommandOptionType::Integer,
-				"level-id",
-				"The ID of the level to request."
-			)
-			.required(true)
-		)
-		.add_option(
-			CreateCommandOption::new(
-				CommandOptionType::String,
-				"request-rating",
-				"The amount of Stars/Moons requested."
-			)
-			.required(true)
-			.add_string_choice("Auto, 1 Star/Moon", "One")
-			.add_string_choice("Easy, 2 Stars/Moons", "Two")
-			.add_string_choice("Normal, 3 Stars/Moons", "Three")
-			.add_string_choice("Hard, 4 Stars/Moons", "Four")
-			.add_string_choice("Hard, 5 Stars/Moons", "Five")
-			.add_string_choice("Harder, 6 Stars/Moons", "Six")
-			.add_string_choice("Harder, 7 Stars/Moons", "Seven")
-			.add_string_choice("Insane, 8 Stars/Moons", "Eight")
-			.add_string_choice("Insane, 9 Stars/Moons", "Nine")
-			.add_string_choice("Demon, 10 Stars/Moons", "Ten")
-		)
-		.add_option(
-			CreateCommandOption::new(
-				CommandOptionType::String,
-				"video-link",
-				"A link to the video showcasing the requested level."
-			)
-			.required(true)
-		)
-		.add_option(
-			CreateCommandOption::new(
-				CommandOptionType::Boolean,
-				"request-feedback",
-				"Request for reviewers to potentially review your request."
-			)
-			.required(true)
-		)
-		.add_option(
-			CreateCommandOption::new(
-				CommandOptionType::Boolean,
-				"notify",
-				"Notify when a review has been made or if the level has been sent."
-			)
-			.required(true)
-		)
+		.set_options(RequestLevelArgs::create_options())
 }
 
-pub async fn run_request_level(ctx: &Context, command: &CommandInteraction) {
-	let level_request = LevelRequest {
-		discord_user_id: u64::from(command.user.id),
-		level_id: command
-			.data
-			.options
-			.get(0)
-			.unwrap()
-			.value
-			.as_i64()
-			.unwrap()
-			.unsigned_abs(),
-		request_score: RequestRating::from_str(
-			command.data.options.get(1).unwrap().value.as_str().unwrap()
-		)
-		.unwrap(),
-		youtube_video_link: command
-			.data
-			.options
-			.get(2)
-			.unwrap()
-			.value
-			.as_str()
-			.unwrap()
-			.to_string(),
-		has_requested_feedback: command
-			.data
-			.options
-			.get(3)
-			.unwrap()
-			.value
-			.as_bool()
-			.unwrap(),
-		notify: command
-			.data
-			.options
-			.get(4)
-			.unwrap()
-			.value
-			.as_bool()
-			.unwrap()
+pub async fn run_request_level(
+	http: &Arc<Http>,
+	services: &Services,
+	command: &CommandInteraction,
+	guild_config: &GuildConfig
+) {
+	let response = DeferredResponse::defer_command(http, command).await;
+	let level_request = match parse_level_request(command) {
+		Ok(level_request) => level_request,
+		Err(error) => {
+			error!("Invalid request-level options: {}", error);
+			response.edit(&error.to_string()).await;
+			return;
+		}
 	};
 
-	let service = LevelRequestService::new();
-	let content: String;
-	match service.request_level(level_request).await {
+	match services
+		.level_request_service
+		.request_level(level_request)
+		.await
+	{
 		Ok(level_data) => {
-			content = "Level has been requested successfully!".to_string();
-			invoke_ephermal(&content, &ctx, &command).await;
-
-			let mut request_message = MessageBuilder::new();
-			request_message
-				.push_line(format!(
-					"\"{}\" by {}",
-					&level_data.level_name, &level_data.level_author
-				))
-				.push_line(format!("{}", &level_data.level_id))
-				.push_line(format!("Requested {}", &level_data.request_score));
-			if level_data.has_requested_feedback {
-				request_message.push_line("Feedback has been requested!");
-			}
-			request_message.push_line(format!("{}", &level_data.youtube_video_link));
+			response
+				.edit("Level has been requested successfully!")
+				.await;
+			audit(
+				AuditEvent::LevelRequested {
+					requester: (&command.user).into(),
+					level_id: level_data.level_id,
+					level_name: level_data.level_name.clone(),
+					request_score: level_data.request_score,
+					level_length: level_data.level_length,
+					has_requested_feedback: level_data.has_requested_feedback
+				},
+				guild_config
+			)
+			.await;
 
-			match ChannelId::new(1193493680594616411)
-				.say(&ctx.http, &request_message.build())
+			let request_message = render_level_request(&level_data)
+				.with_components(create_request_action_buttons(level_data.level_id));
+			if let Err(error) = services
+				.level_request_service
+				.post_level_request(guild_config, &level_data, &request_message)
 				.await
 			{
-				Ok(msg) => {
-					let update_request_message_id = UpdateLevelRequestMessageId {
-						level_id: level_data.level_id,
-						discord_message_id: msg.id.get()
-					};
-					if let Err(error) = &service
-						.update_request_message_id(update_request_message_id)
-						.await
-					{
-						error!("Error updating message ID: {error:?}");
-					}
-
-					{
-						let mut log_message = MessageBuilder::new();
-						log_message.push_bold(format!("{} ", command.user.name));
-						log_message
-							.push_line(format!("({}) has requested a level", command.user.id));
-						log_message.push_codeblock(format!("{:?}", &level_data), Some("rust"));
-						log_to_discord(log_message.build(), ctx.clone()).await
-					}
-				}
-				Err(error) => {
-					error!("Error sending message: {error:?}");
-				}
+				error!("Error sending message: {error:?}");
+				response
+					.follow_up("Unable to post the request message to Discord.")
+					.await;
 			}
 		}
 		Err(error) => {
-			match error {
-				LevelRequestError::LevelRequestExists => {
-					content = "Level has already been requested.".to_string();
-					invoke_ephermal(&content, &ctx, &command).await;
-				}
-				LevelRequestError::RequestError => {
-					content = "There was an error making the request.".to_string();
-					invoke_ephermal(&content, &ctx, &command).await;
-				}
-				LevelRequestError::SerializeError => {
-					content = "There was an error making the request.".to_string();
-					invoke_ephermal(&content, &ctx, &command).await;
-				}
-				LevelRequestError::RequestXApiError => {
-					content = "There was an error making the request.".to_string();
-					invoke_ephermal(&content, &ctx, &command).await;
-				}
-			}
+			response.edit(&level_request_error_message(&error)).await;
 
-			{
-				let mut log_message = MessageBuilder::new();
-				log_message.push_bold(format!("{} ", command.user.name));
-				log_message.push_line(format!(
-					"({}) cause an error when requesting a level",
-					command.user.id
-				));
-				log_message.push_codeblock(format!("{:?}", error), Some("rust"));
-				log_to_discord(log_message.build(), ctx.clone()).await
-			}
+			audit(
+				AuditEvent::Error {
+					user: (&command.user).into(),
+					action: "request-level".to_string(),
+					error: error.to_string()
+				},
+				guild_config
+			)
+			.await
 		}
 	}
 }
+
+pub fn level_request_error_message(error: &LevelRequestError) -> String {
+	match error {
+		LevelRequestError::LevelRequestExists => "Level has already been requested.".to_string(),
+		LevelRequestError::InvalidVideoLink => {
+			"The video link is not a valid YouTube link.".to_string()
+		}
+		LevelRequestError::RequestX(requestx_error) => requestx_error.user_message()
+	}
+}
+
+fn parse_level_request(command: &CommandInteraction) -> Result<LevelRequest, CommandOptionError> {
+	let args = RequestLevelArgs::parse(&command.data.options)?;
+	Ok(LevelRequest {
+		discord_user_id: u64::from(command.user.id),
+		level_id: args.level_id,
+		request_score: args.request_rating,
+		youtube_video_link: args.video_link,
+		has_requested_feedback: args.request_feedback,
+		notify: args.notify
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use httpmock::{
+		Method::{PATCH, POST},
+		Mock, MockServer
+	};
+	use serde_json::json;
+	use serenity::all::CommandInteraction;
+
+	use crate::{
+		commands::request_level::run_request_level,
+		config::requestx_api_config::REQUESTX_API_CONFIG,
+		test_harness::{
+			command_interaction, init_mock_server, mock_create_message, mock_deferred_response,
+			mock_edited_response, mock_follow_up, test_guild_config, test_http, test_services,
+			TEST_USER_ID
+		}
+	};
+
+	async fn mock_request_level(server: &MockServer) -> Mock<'_> {
+		server
+			.mock_async(|when, then| {
+				when.method(POST)
+					.path(&*REQUESTX_API_CONFIG.paths.request_level)
+					.json_body_partial(r#"{"level_id": 97624039, "request_rating": "Four"}"#);
+				then.status(201).json_body(json!({
+					"level_id": 97624039,
+					"discord_id": TEST_USER_ID,
+					"discord_message_id": null,
+					"discord_thread_id": null,
+					"level_name": "Some Level",
+					"level_author": "Some Author",
+					"request_score": "Four",
+					"level_length": "Short",
+					"youtube_video_link": "https://youtu.be/dQw4w9WgXcQ",
+					"has_requested_feedback": false,
+					"notify": false
+				}));
+			})
+			.await
+	}
+
+	fn request_level_command() -> CommandInteraction {
+		command_interaction(
+			"request-level",
+			json!([
+				{ "name": "level-id", "type": 4, "value": 97624039 },
+				{ "name": "request-rating", "type": 3, "value": "Four" },
+				{ "name": "video-link", "type": 3, "value": "https://youtu.be/dQw4w9WgXcQ" },
+				{ "name": "request-feedback", "type": 5, "value": false },
+				{ "name": "notify", "type": 5, "value": false }
+			]),
+			json!({})
+		)
+	}
+
+	#[tokio::test]
+	async fn request_level_should_post_request_and_store_message_id() {
+		let server = init_mock_server().await;
+		let requestx_mock = mock_request_level(&server).await;
+		let update_message_id_mock = server
+			.mock_async(|when, then| {
+				when.method(PATCH)
+					.path(&*REQUESTX_API_CONFIG.paths.update_request_message_id)
+					.json_body_partial(r#"{"level_id": 97624039, "discord_message_id": 300}"#);
+				then.status(200);
+			})
+			.await;
+		let deferred_response_mock = mock_deferred_response(&server).await;
+		let interaction_response_mock =
+			mock_edited_response(&server, "Level has been requested successfully!").await;
+		let request_message_mock =
+			mock_create_message(&server, test_guild_config().discord_request_channel_id, 300).await;
+
+		run_request_level(
+			&test_http(&server),
+			&test_services(&server),
+			&request_level_command(),
+			test_guild_config()
+		)
+		.await;
+
+		requestx_mock.assert_async().await;
+		deferred_response_mock.assert_async().await;
+		interaction_response_mock.assert_async().await;
+		request_message_mock.assert_async().await;
+		update_message_id_mock.assert_async().await;
+	}
+
+	#[tokio::test]
+	async fn request_level_should_follow_up_when_request_message_fails() {
+		let server = init_mock_server().await;
+		let requestx_mock = mock_request_level(&server).await;
+		let request_message_mock = server
+			.mock_async(|when, then| {
+				when.method(POST).path(format!(
+					"/api/v10/channels/{}/messages",
+					test_guild_config().discord_request_channel_id
+				));
+				then.status(403)
+					.json_body(json!({ "code": 50013, "message": "Missing Permissions" }));
+			})
+			.await;
+		let interaction_response_mock =
+			mock_edited_response(&server, "Level has been requested successfully!").await;
+		let follow_up_mock =
+			mock_follow_up(&server, "Unable to post the request message to Discord.").await;
+
+		run_request_level(
+			&test_http(&server),
+			&test_services(&server),
+			&request_level_command(),
+			test_guild_config()
+		)
+		.await;
+
+		requestx_mock.assert_async().await;
+		request_message_mock.assert_async().await;
+		interaction_response_mock.assert_async().await;
+		follow_up_mock.assert_async().await;
+	}
+}
@@ -0,0 +1,360 @@
+use std::sync::Arc;
+
+use log::error;
+use serenity::all::{CommandInteraction, CreateCommand, Http};
+
+use crate::{
+	commands::request_level::{level_request_error_message, REQUEST_RATING_CHOICES},
+	config::client_config::GuildConfig,
+	model::{
+		audit_event::AuditEvent,
+		level_request::{GetLevelRequest, UpdateLevelRequest},
+		request_score::RequestRating,
+		requestx_api::level_request_data::LevelRequestData
+	},
+	service::services::Services,
+	util::{
+		audit::audit,
+		command_options::{command_args, CommandOption},
+		discord::DeferredResponse
+	}
+};
+
+command_args! {
+	pub struct RequestEditArgs {
+		level_id: u64 = CommandOption::unsigned_integer(
+			"level-id",
+			"The level ID of your request."
+		),
+		request_rating: Option<RequestRating> = CommandOption::choice(
+			"request-rating",
+			"The new amount of Stars/Moons requested.",
+			&REQUEST_RATING_CHOICES
+		)
+		.optional(),
+		video_link: Option<String> = CommandOption::string(
+			"video-link",
+			"A new link to the video showcasing the requested level."
+		)
+		.optional(),
+		request_feedback: Option<bool> = CommandOption::boolean(
+			"request-feedback",
+			"Request for reviewers to potentially review your request."
+		)
+		.optional(),
+		notify: Option<bool> = CommandOption::boolean(
+			"notify",
+			"Notify when a review has been made or if the level has been sent."
+		)
+		.optional()
+	}
+}
+
+command_args! {
+	pub struct RequestWithdrawArgs {
+		level_id: u64 = CommandOption::unsigned_integer(
+			"level-id",
+			"The level ID of the request to withdraw."
+		)
+	}
+}
+
+pub fn register_request_edit() -> CreateCommand {
+	CreateCommand::new("request-edit")
+		.description("Change one of your level requests")
+		.set_options(RequestEditArgs::create_options())
+}
+
+pub fn register_request_withdraw() -> CreateCommand {
+	CreateCommand::new("request-withdraw")
+		.description("Withdraw one of your level requests")
+		.set_options(RequestWithdrawArgs::create_options())
+}
+
+pub async fn run_request_edit(
+	http: &Arc<Http>,
+	services: &Services,
+	command: &CommandInteraction,
+	guild_config: &GuildConfig
+) {
+	let response = DeferredResponse::defer_command(http, command).await;
+	let args = match RequestEditArgs::parse(&command.data.options) {
+		Ok(args) => args,
+		Err(error) => {
+			error!("Invalid request-edit options: {}", error);
+			response.edit(&error.to_string()).await;
+			return;
+		}
+	};
+	if args.request_rating.is_none()
+		&& args.video_link.is_none()
+		&& args.request_feedback.is_none()
+		&& args.notify.is_none()
+	{
+		response.edit("Nothing to change.").await;
+		return;
+	}
+
+	if let Err(content) = get_own_open_level_request(services, command, args.level_id).await {
+		response.edit(&content).await;
+		return;
+	}
+
+	let update_level_request = UpdateLevelRequest {
+		level_id: args.level_id,
+		discord_user_id: command.user.id.get(),
+		request_score: args.request_rating,
+		youtube_video_link: args.video_link,
+		has_requested_feedback: args.request_feedback,
+		notify: args.notify
+	};
+	match services
+		.level_request_service
+		.edit_level_request(guild_config, update_level_request)
+		.await
+	{
+		Ok(level_request) => {
+			response.edit("Your request has been updated.").await;
+			audit(
+				AuditEvent::LevelRequestEdited {
+					requester: (&command.user).into(),
+					level_id: level_request.level_id,
+					level_name: level_request.level_name,
+					request_score: level_request.request_score,
+					has_requested_feedback: level_request.has_requested_feedback,
+					notify: level_request.notify
+				},
+				guild_config
+			)
+			.await;
+		}
+		Err(error) => {
+			response.edit(&level_request_error_message(&error)).await;
+			audit(
+				AuditEvent::Error {
+					user: (&command.user).into(),
+					action: "request-edit".to_string(),
+					error: error.to_string()
+				},
+				guild_config
+			)
+			.await;
+		}
+	}
+}
+
+pub async fn run_request_withdraw(
+	http: &Arc<Http>,
+	services: &Services,
+	command: &CommandInteraction,
+	guild_config: &GuildConfig
+) {
+	let response = DeferredResponse::defer_command(http, command).await;
+	let level_id = match RequestWithdrawArgs::parse(&command.data.options) {
+		Ok(args) => args.level_id,
+		Err(error) => {
+			error!("Invalid request-withdraw options: {}", error);
+			response.edit(&error.to_string()).await;
+			return;
+		}
+	};
+
+	let level_request = match get_own_open_level_request(services, command, level_id).await {
+		Ok(level_request) => level_request,
+		Err(content) => {
+			response.edit(&content).await;
+			return;
+		}
+	};
+
+	match services
+		.level_request_service
+		.withdraw_level_request(guild_config, &level_request)
+		.await
+	{
+		Ok(()) => {
+			response.edit("Your request has been withdrawn.").await;
+			audit(
+				AuditEvent::LevelRequestWithdrawn {
+					requester: (&command.user).into(),
+					level_id: level_request.level_id,
+					level_name: level_request.level_name
+				},
+				guild_config
+			)
+			.await;
+		}
+		Err(error) => {
+			response.edit(&level_request_error_message(&error)).await;
+			audit(
+				AuditEvent::Error {
+					user: (&command.user).into(),
+					action: "request-withdraw".to_string(),
+					error: error.to_string()
+				},
+				guild_config
+			)
+			.await;
+		}
+	}
+}
+
+// Requests can only be changed by whoever made them, and only until a
+// moderator has decided whether to send the level
+async fn get_own_open_level_request(
+	services: &Services,
+	command: &CommandInteraction,
+	level_id: u64
+) -> Result<LevelRequestData, String> {
+	match services
+		.level_request_service
+		.get_level_request(GetLevelRequest { level_id })
+		.await
+	{
+		Ok(Some(level_request)) if level_request.discord_id != command.user.id.get() => {
+			Err("You can only change your own requests.".to_string())
+		}
+		Ok(Some(level_request)) if level_request.suggested_score.is_some() => {
+			Err("This request has already been handled by a moderator.".to_string())
+		}
+		Ok(Some(level_request)) => Ok(level_request),
+		Ok(None) => Err("This level has not been requested.".to_string()),
+		Err(error) => {
+			error!("Unable to get level request: {}", error);
+			Err(level_request_error_message(&error))
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+
+	use serde_json::json;
+
+	use crate::{
+		commands::request_manage::{run_request_edit, run_request_withdraw},
+		model::{
+			level_request::{
+				GetLevelRequest, LevelRequest, UpdateLevelRequestMessageId,
+				UpdateLevelRequestThreadId
+			},
+			request_score::RequestRating,
+			requestx_api::{
+				in_memory_requestx_api::InMemoryRequestXApi, requestx_api_trait::RequestXApi
+			}
+		},
+		service::services::Services,
+		test_harness::{
+			command_interaction, init_mock_server, mock_deferred_response, mock_edited_response,
+			test_guild_config, test_http, DiscordGatewayCall, RecordingDiscordGateway,
+			TEST_USER_ID
+		}
+	};
+
+	async fn request_level(requestx_api: &InMemoryRequestXApi, discord_user_id: u64) {
+		requestx_api
+			.make_requestx_api_level_request(LevelRequest {
+				discord_user_id,
+				level_id: 97624039,
+				request_score: RequestRating::Four,
+				youtube_video_link: "https://youtu.be/dQw4w9WgXcQ".to_string(),
+				has_requested_feedback: false,
+				notify: false
+			})
+			.await
+			.unwrap();
+		requestx_api
+			.update_request_message_id(UpdateLevelRequestMessageId {
+				level_id: 97624039,
+				discord_message_id: 300,
+				discord_channel_id: 1
+			})
+			.await
+			.unwrap();
+		requestx_api
+			.update_request_thread_id(UpdateLevelRequestThreadId {
+				level_id: 97624039,
+				discord_thread_id: 301
+			})
+			.await
+			.unwrap();
+	}
+
+	#[tokio::test]
+	async fn request_withdraw_should_delete_request_message_and_thread() {
+		let server = init_mock_server().await;
+		let requestx_api = Arc::new(InMemoryRequestXApi::default());
+		request_level(&requestx_api, TEST_USER_ID).await;
+		let discord_gateway = Arc::new(RecordingDiscordGateway::default());
+		let deferred_response_mock = mock_deferred_response(&server).await;
+		let withdrawn_response_mock =
+			mock_edited_response(&server, "Your request has been withdrawn.").await;
+
+		run_request_withdraw(
+			&test_http(&server),
+			&Services::new(requestx_api.clone(), discord_gateway.clone()),
+			&command_interaction(
+				"request-withdraw",
+				json!([{ "name": "level-id", "type": 4, "value": 97624039 }]),
+				json!({})
+			),
+			test_guild_config()
+		)
+		.await;
+
+		deferred_response_mock.assert_async().await;
+		withdrawn_response_mock.assert_async().await;
+		assert!(requestx_api
+			.get_level_request(GetLevelRequest { level_id: 97624039 })
+			.await
+			.unwrap()
+			.is_none());
+		assert_eq!(
+			*discord_gateway.calls.lock().unwrap(),
+			vec![
+				DiscordGatewayCall::DeleteThread { thread_id: 301 },
+				DiscordGatewayCall::DeleteMessage {
+					channel_id: test_guild_config().discord_request_channel_id,
+					message_id: 300
+				}
+			]
+		);
+	}
+
+	#[tokio::test]
+	async fn request_edit_should_reject_requests_of_other_users() {
+		let server = init_mock_server().await;
+		let requestx_api = Arc::new(InMemoryRequestXApi::default());
+		request_level(&requestx_api, 555).await;
+		let discord_gateway = Arc::new(RecordingDiscordGateway::default());
+		let rejected_response_mock =
+			mock_edited_response(&server, "You can only change your own requests.").await;
+
+		run_request_edit(
+			&test_http(&server),
+			&Services::new(requestx_api.clone(), discord_gateway.clone()),
+			&command_interaction(
+				"request-edit",
+				json!([
+					{ "name": "level-id", "type": 4, "value": 97624039 },
+					{ "name": "notify", "type": 5, "value": true }
+				]),
+				json!({})
+			),
+			test_guild_config()
+		)
+		.await;
+
+		rejected_response_mock.assert_async().await;
+		assert!(
+			!requestx_api
+				.get_level_request(GetLevelRequest { level_id: 97624039 })
+				.await
+				.unwrap()
+				.unwrap()
+				.notify
+		);
+		assert!(discord_gateway.calls.lock().unwrap().is_empty());
+	}
+}
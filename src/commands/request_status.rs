@@ -0,0 +1,267 @@
+use std::sync::Arc;
+
+use log::error;
+use serenity::all::{CommandInteraction, CreateCommand, Http};
+
+use crate::{
+	config::client_config::GuildConfig,
+	model::{
+		error::{level_request_error::LevelRequestError, requestx_error::RequestXError},
+		level_request::{GetLevelRequest, GetLevelReviews, GetRequesterLevelRequests},
+		requestx_api::level_request_data::LevelRequestData
+	},
+	service::services::Services,
+	util::{
+		command_options::{command_args, CommandOption},
+		discord::DeferredResponse,
+		render::render_request_status
+	}
+};
+
+const REQUEST_STATUS_PAGE_SIZE: u64 = 5;
+
+command_args! {
+	pub struct RequestStatusArgs {
+		level_id: Option<u64> = CommandOption::unsigned_integer(
+			"level-id",
+			"The level ID of your request, leave empty to list your latest requests."
+		)
+		.optional()
+	}
+}
+
+pub fn register_request_status() -> CreateCommand {
+	CreateCommand::new("request-status")
+		.description("Shows what happened to your level requests")
+		.set_options(RequestStatusArgs::create_options())
+}
+
+pub async fn run_request_status(
+	http: &Arc<Http>,
+	services: &Services,
+	command: &CommandInteraction,
+	guild_config: &GuildConfig
+) {
+	let response = DeferredResponse::defer_command(http, command).await;
+	let level_id = match RequestStatusArgs::parse(&command.data.options) {
+		Ok(args) => args.level_id,
+		Err(error) => {
+			error!("Invalid request-status options: {}", error);
+			response.edit(&error.to_string()).await;
+			return;
+		}
+	};
+
+	let content = match get_own_level_requests(services, command.user.id.get(), level_id).await {
+		Ok(level_requests) if level_requests.is_empty() => match level_id {
+			Some(_) => "You have not requested this level.".to_string(),
+			None => "You have not requested any levels yet.".to_string()
+		},
+		Ok(level_requests) => {
+			let mut request_statuses = Vec::new();
+			for level_request in &level_requests {
+				let review_count = match services
+					.level_review_service
+					.get_level_reviews(GetLevelReviews {
+						level_id: level_request.level_id
+					})
+					.await
+				{
+					Ok(level_reviews) => Some(level_reviews.len()),
+					Err(error) => {
+						error!("Unable to get level reviews: {}", error);
+						None
+					}
+				};
+				request_statuses.push(render_request_status(
+					level_request,
+					review_count,
+					guild_config
+				));
+			}
+			request_statuses.join("\n")
+		}
+		Err(error) => {
+			error!("Unable to get level requests: {}", error);
+			match error {
+				LevelRequestError::RequestX(requestx_error @ RequestXError::Unavailable) => {
+					requestx_error.user_message()
+				}
+				_ => "There was an error getting your requests.".to_string()
+			}
+		}
+	};
+	response.edit(&content).await;
+}
+
+// Requesters only ever see their own requests, even when they ask for a
+// level someone else requested
+async fn get_own_level_requests(
+	services: &Services,
+	discord_user_id: u64,
+	level_id: Option<u64>
+) -> Result<Vec<LevelRequestData>, LevelRequestError> {
+	match level_id {
+		Some(level_id) => Ok(services
+			.level_request_service
+			.get_level_request(GetLevelRequest { level_id })
+			.await?
+			.filter(|level_request| level_request.discord_id == discord_user_id)
+			.into_iter()
+			.collect()),
+		None => Ok(services
+			.level_request_service
+			.get_requester_level_requests(GetRequesterLevelRequests {
+				discord_user_id,
+				page: 0,
+				page_size: REQUEST_STATUS_PAGE_SIZE
+			})
+			.await?
+			.level_requests
+			.into_iter()
+			.filter(|level_request| level_request.discord_id == discord_user_id)
+			.collect())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+
+	use httpmock::Method::GET;
+	use serde_json::json;
+
+	use crate::{
+		commands::request_status::run_request_status,
+		config::requestx_api_config::REQUESTX_API_CONFIG,
+		model::{
+			level_request::{LevelRequest, UpdateLevelRequestMessageId},
+			level_review::LevelReview,
+			moderator::{Moderator, SuggestedRating, SuggestedScore},
+			request_score::RequestRating,
+			requestx_api::{
+				in_memory_requestx_api::InMemoryRequestXApi, requestx_api_trait::RequestXApi
+			}
+		},
+		service::services::Services,
+		test_harness::{
+			command_interaction, init_mock_server, mock_deferred_response, mock_edited_response,
+			test_guild_config, test_http, test_services, RecordingDiscordGateway, TEST_USER_ID
+		}
+	};
+
+	async fn request_level(
+		requestx_api: &InMemoryRequestXApi,
+		discord_user_id: u64,
+		level_id: u64
+	) {
+		requestx_api
+			.make_requestx_api_level_request(LevelRequest {
+				discord_user_id,
+				level_id,
+				request_score: RequestRating::Four,
+				youtube_video_link: "https://youtu.be/dQw4w9WgXcQ".to_string(),
+				has_requested_feedback: true,
+				notify: false
+			})
+			.await
+			.unwrap();
+	}
+
+	#[tokio::test]
+	async fn request_status_should_list_own_requests_only() {
+		let server = init_mock_server().await;
+		let requestx_api = Arc::new(InMemoryRequestXApi::default());
+		request_level(&requestx_api, TEST_USER_ID, 97624039).await;
+		request_level(&requestx_api, 555, 128).await;
+		requestx_api
+			.update_request_message_id(UpdateLevelRequestMessageId {
+				level_id: 97624039,
+				discord_message_id: 300,
+				discord_channel_id: 1
+			})
+			.await
+			.unwrap();
+		requestx_api
+			.make_requestx_api_level_review_request(&LevelReview {
+				discord_user_id: 555,
+				discord_message_id: 301,
+				level_id: 97624039,
+				review_contents: "Nice level".to_string()
+			})
+			.await
+			.unwrap();
+		requestx_api
+			.make_send_level_request(Moderator {
+				level_id: 97624039,
+				suggested_score: SuggestedScore::Four,
+				suggested_rating: SuggestedRating::Feature
+			})
+			.await
+			.unwrap();
+		let deferred_response_mock = mock_deferred_response(&server).await;
+		let other_request_mock = mock_edited_response(&server, "(128)").await;
+		let status_response_mock = mock_edited_response(
+			&server,
+			r"Status: Sent for Feature, Four Stars\nReviews: 1\nRequest message: https://discord.com/channels/1/1/300\nThread: Not opened yet"
+		)
+		.await;
+		let services = Services::new(requestx_api, Arc::new(RecordingDiscordGateway::default()));
+
+		run_request_status(
+			&test_http(&server),
+			&services,
+			&command_interaction("request-status", json!([]), json!({})),
+			test_guild_config()
+		)
+		.await;
+
+		deferred_response_mock.assert_async().await;
+		status_response_mock.assert_async().await;
+		assert_eq!(other_request_mock.hits_async().await, 0);
+	}
+
+	#[tokio::test]
+	async fn request_status_should_hide_requests_of_other_users_from_the_server() {
+		let server = init_mock_server().await;
+		let requester_level_requests_mock = server
+			.mock_async(|when, then| {
+				when.method(GET)
+					.path(&*REQUESTX_API_CONFIG.paths.request_level)
+					.query_param("discord_id", TEST_USER_ID.to_string());
+				then.status(200).json_body(json!({
+					"level_requests": [{
+						"level_id": 128,
+						"discord_id": 555,
+						"discord_message_id": null,
+						"discord_thread_id": null,
+						"level_name": "Other Level",
+						"level_author": "Some Author",
+						"request_score": "Four",
+						"level_length": "Short",
+						"youtube_video_link": "https://youtu.be/dQw4w9WgXcQ",
+						"has_requested_feedback": false,
+						"notify": false
+					}],
+					"page": 0,
+					"total_pages": 1
+				}));
+			})
+			.await;
+		let deferred_response_mock = mock_deferred_response(&server).await;
+		let empty_response_mock =
+			mock_edited_response(&server, "You have not requested any levels yet.").await;
+
+		run_request_status(
+			&test_http(&server),
+			&test_services(&server),
+			&command_interaction("request-status", json!([]), json!({})),
+			test_guild_config()
+		)
+		.await;
+
+		requester_level_requests_mock.assert_async().await;
+		deferred_response_mock.assert_async().await;
+		empty_response_mock.assert_async().await;
+	}
+}
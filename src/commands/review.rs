@@ -1,96 +1,467 @@
+use std::{sync::Arc, time::Duration};
+
+use log::{error, warn};
 use serenity::all::{
-	CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption,
-	MessageBuilder
+	ActionRowComponent, CommandInteraction, CreateActionRow, CreateCommand, CreateInputText,
+	CreateInteractionResponse, CreateModal, Http, InputTextStyle, ModalInteraction, User
 };
+use tokio::time::timeout;
 
 use crate::{
-	config::client_config::CLIENT_CONFIG,
-	service::level_review_service::LevelReviewService,
-	util::discord::{invoke_ephermal, log_to_discord}
+	config::client_config::GuildConfig,
+	model::{
+		audit_event::AuditEvent, discord_user::DiscordUser, level_request::GetLevelReview,
+		level_review::LevelReviewOutcome, permission::Permission,
+		requestx_api::level_review_error::LevelReviewError
+	},
+	service::services::Services,
+	util::{
+		audit::audit,
+		command_options::{command_args, CommandOption},
+		discord::{invoke_ephermal, DeferredResponse},
+		permission::{authorize_modal, get_permission}
+	}
 };
 
+pub const REVIEW_MODAL_CUSTOM_ID_PREFIX: &str = "review";
+const REVIEW_CONTENTS_INPUT_ID: &str = "review-contents";
+const REVIEW_PREFILL_TIMEOUT: Duration = Duration::from_millis(1500);
+
+command_args! {
+	pub struct ReviewArgs {
+		level_id: u64 = CommandOption::unsigned_integer(
+			"level-id",
+			"The level ID of the request to review."
+		)
+		.autocomplete()
+	}
+}
+
 pub fn register_review() -> CreateCommand {
 	CreateCommand::new("review")
 		.description("Submit a review for the given level")
-		.add_option(
-			CreateCommandOption::new(
-				CommandOptionType::Integer,
-				"level-id",
-				"The level ID of the request to review."
-			)
-			.required(true)
-		)
-		.add_option(
-			CreateCommandOption::new(
-				CommandOptionType::String,
-				"review-contents",
-				"The review to be shared with the Discord user who requested the level."
-			)
-			.required(true)
+		.set_options(ReviewArgs::create_options())
+}
+
+pub async fn post_level_review(
+	http: &Arc<Http>,
+	services: &Services,
+	command: &CommandInteraction
+) {
+	let level_id = match ReviewArgs::parse(&command.data.options) {
+		Ok(args) => args.level_id,
+		Err(error) => {
+			error!("Invalid review options: {}", error);
+			invoke_ephermal(&error.to_string(), http, command).await;
+			return;
+		}
+	};
+
+	// A modal has to be the initial response, so unlike the other commands this
+	// one cannot be deferred
+	let builder = CreateInteractionResponse::Modal(
+		create_review_modal(services, level_id, &command.user).await
+	);
+	if let Err(err) = command.create_response(http, builder).await {
+		error!("Cannot respond to slash command: {err}");
+	}
+}
+
+pub async fn create_review_modal(
+	services: &Services,
+	level_id: u64,
+	reviewer: &User
+) -> CreateModal {
+	let get_level_review = GetLevelReview {
+		discord_user_id: reviewer.id.get(),
+		level_id
+	};
+	// The modal is the initial response, an existing review is only prefilled
+	// if RequestX answers well within Discord's three second window
+	let existing_review_contents = match timeout(
+		REVIEW_PREFILL_TIMEOUT,
+		services
+			.level_review_service
+			.get_level_review_once(get_level_review)
+	)
+	.await
+	{
+		Ok(Ok(potential_level_review)) => {
+			potential_level_review.map(|level_review| level_review.review_contents)
+		}
+		Ok(Err(error)) => {
+			error!("Unable to get existing level review: {}", error);
+			None
+		}
+		Err(_) => {
+			warn!("Timed out getting existing level review for {}", level_id);
+			None
+		}
+	};
+
+	let mut review_input = CreateInputText::new(
+		InputTextStyle::Paragraph,
+		"Review",
+		REVIEW_CONTENTS_INPUT_ID
+	)
+	.placeholder("The review to be shared with the Discord user who requested the level.")
+	.max_length(4000);
+	if let Some(review_contents) = existing_review_contents {
+		review_input = review_input.value(review_contents);
+	}
+
+	CreateModal::new(
+		format!("{}:{}", REVIEW_MODAL_CUSTOM_ID_PREFIX, level_id),
+		format!("Review level {}", level_id)
+	)
+	.components(vec![CreateActionRow::InputText(review_input)])
+}
+
+pub async fn run_review_modal(
+	http: &Arc<Http>,
+	services: &Services,
+	modal: &ModalInteraction,
+	guild_config: &GuildConfig
+) {
+	let level_id = modal
+		.data
+		.custom_id
+		.split(':')
+		.nth(1)
+		.and_then(|level_id| level_id.parse::<u64>().ok());
+	let review_contents = modal
+		.data
+		.components
+		.iter()
+		.flat_map(|action_row| action_row.components.iter())
+		.find_map(|component| match component {
+			ActionRowComponent::InputText(input_text)
+				if input_text.custom_id == REVIEW_CONTENTS_INPUT_ID =>
+			{
+				input_text.value.clone()
+			}
+			_ => None
+		});
+
+	if !authorize_modal(http, modal, guild_config, Permission::Reviewer).await {
+		return;
+	}
+
+	let response = DeferredResponse::defer_modal(http, modal).await;
+	if let (Some(level_id), Some(review_contents)) = (level_id, review_contents) {
+		let reviewer_permission = get_permission(&modal.user, modal.member.as_ref(), guild_config);
+		let content = submit_level_review(
+			services,
+			&DiscordUser::from(&modal.user),
+			reviewer_permission,
+			guild_config,
+			level_id,
+			review_contents
 		)
+		.await;
+		response.edit(&content).await
+	} else {
+		error!("Invalid review modal submission: {}", modal.data.custom_id);
+		response.edit("Unable to submit review").await
+	}
 }
 
-pub async fn post_level_review(ctx: &Context, command: &CommandInteraction) {
-	let content: String;
-	if !command
-		.user
-		.has_role(
-			&ctx.http,
-			CLIENT_CONFIG.discord_guild_id,
-			CLIENT_CONFIG.discord_reviewer_role_id
+async fn submit_level_review(
+	services: &Services,
+	reviewer: &DiscordUser,
+	reviewer_permission: Option<Permission>,
+	guild_config: &GuildConfig,
+	level_id: u64,
+	review_contents: String
+) -> String {
+	match services
+		.level_review_service
+		.review_level(
+			reviewer,
+			reviewer_permission,
+			guild_config,
+			level_id,
+			review_contents
 		)
 		.await
-		.unwrap()
 	{
-		content = "Forbidden".to_string();
-		invoke_ephermal(&content, &ctx, &command).await;
-	} else {
-		let reviewer_discord_user_id = command.user.id.get();
-		let level_id = command
-			.data
-			.options
-			.get(0)
-			.unwrap()
-			.value
-			.as_i64()
-			.unwrap()
-			.unsigned_abs();
-		let review_contents = command
-			.data
-			.options
-			.get(1)
-			.unwrap()
-			.value
-			.as_str()
-			.unwrap()
-			.to_string();
-		let level_review_service = LevelReviewService::new();
-
-		match level_review_service
-			.review_level(
-				&ctx,
-				&command,
-				level_id,
-				reviewer_discord_user_id,
-				review_contents
-			)
-			.await
-		{
-			Ok(message_string) => {
-				{
-					let mut log_message = MessageBuilder::new();
-					log_message.push_bold(format!("{} ", command.user.name));
-					log_message.push_line(format!(
-						"({}) left a review on level request ID: {}",
-						command.user.id, level_id
-					));
-					log_to_discord(log_message.build(), ctx.clone()).await
-				}
-				invoke_ephermal(&message_string, &ctx, &command).await
+		Ok(level_review_outcome) => {
+			let audit_event = match level_review_outcome {
+				LevelReviewOutcome::Posted => Some(AuditEvent::ReviewPosted {
+					reviewer: reviewer.into(),
+					level_id
+				}),
+				LevelReviewOutcome::Edited => Some(AuditEvent::ReviewEdited {
+					reviewer: reviewer.into(),
+					level_id
+				}),
+				LevelReviewOutcome::FeedbackNotRequested => None
+			};
+			if let Some(audit_event) = audit_event {
+				audit(audit_event, guild_config).await;
 			}
-			Err(level_review_error) => {
-				invoke_ephermal(&level_review_error.to_string(), &ctx, &command).await
+			level_review_outcome.to_string()
+		}
+		Err(level_review_error) => {
+			error!("Unable to submit review: {}", level_review_error);
+			audit(
+				AuditEvent::Error {
+					user: reviewer.into(),
+					action: format!("review {}", level_id),
+					error: level_review_error.to_string()
+				},
+				guild_config
+			)
+			.await;
+			level_review_error_message(&level_review_error)
+		}
+	}
+}
+
+fn level_review_error_message(error: &LevelReviewError) -> String {
+	match error {
+		LevelReviewError::LevelRequestDoesNotExists => "Level request does not exist.".to_string(),
+		LevelReviewError::RequestX(requestx_error) => requestx_error.user_message(),
+		LevelReviewError::Discord(_) => "Unable to post the review on Discord.".to_string()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{
+		sync::Arc,
+		time::{Duration, Instant}
+	};
+
+	use httpmock::Method::{GET, PATCH};
+	use serde_json::json;
+
+	use crate::{
+		commands::review::{
+			post_level_review, run_review_modal, REVIEW_CONTENTS_INPUT_ID,
+			REVIEW_MODAL_CUSTOM_ID_PREFIX
+		},
+		config::{common_config::APP_CONFIG, requestx_api_config::REQUESTX_API_CONFIG},
+		model::{
+			level_request::{GetLevelReview, LevelRequest, UpdateLevelRequestMessageId},
+			request_score::RequestRating,
+			requestx_api::{
+				in_memory_requestx_api::InMemoryRequestXApi, requestx_api_trait::RequestXApi
 			}
+		},
+		service::services::Services,
+		test_harness::{
+			command_interaction, discord_message, init_mock_server, mock_deferred_response,
+			mock_edited_response, mock_interaction_response, modal_interaction, test_guild_config,
+			test_http, test_services, DiscordGatewayCall, RecordingDiscordGateway,
+			TEST_REVIEWER_ROLE_ID, TEST_USER_ID
+		}
+	};
+
+	#[tokio::test]
+	async fn review_should_open_modal_for_level() {
+		let server = init_mock_server().await;
+		let existing_review_mock = server
+			.mock_async(|when, then| {
+				when.method(GET).path(format!(
+					"{}/97624039",
+					REQUESTX_API_CONFIG.paths.review_level
+				));
+				then.status(404);
+			})
+			.await;
+		let modal_response_mock =
+			mock_interaction_response(&server, r#""custom_id":"review:97624039""#).await;
+		let command = command_interaction(
+			"review",
+			json!([{ "name": "level-id", "type": 4, "value": 97624039 }]),
+			json!({})
+		);
+
+		post_level_review(&test_http(&server), &test_services(&server), &command).await;
+
+		existing_review_mock.assert_async().await;
+		modal_response_mock.assert_async().await;
+	}
+
+	#[tokio::test]
+	async fn review_should_open_empty_modal_when_existing_review_is_unavailable() {
+		for (status, delay) in [(500, Duration::ZERO), (200, Duration::from_secs(3))] {
+			let server = init_mock_server().await;
+			let existing_review_mock = server
+				.mock_async(|when, then| {
+					when.method(GET).path(format!(
+						"{}/97624039",
+						REQUESTX_API_CONFIG.paths.review_level
+					));
+					then.status(status).delay(delay).json_body(json!({
+						"level_id": 97624039,
+						"reviewer_discord_id": TEST_USER_ID,
+						"discord_message_id": 301,
+						"review_contents": "Nice level"
+					}));
+				})
+				.await;
+			let modal_response_mock =
+				mock_interaction_response(&server, r#""custom_id":"review:97624039""#).await;
+			let command = command_interaction(
+				"review",
+				json!([{ "name": "level-id", "type": 4, "value": 97624039 }]),
+				json!({})
+			);
+
+			let started_at = Instant::now();
+			post_level_review(&test_http(&server), &test_services(&server), &command).await;
+
+			assert!(started_at.elapsed() < Duration::from_secs(2));
+			assert_eq!(existing_review_mock.hits_async().await, 1);
+			modal_response_mock.assert_async().await;
 		}
 	}
+
+	#[tokio::test]
+	async fn review_modal_should_post_reviews_from_reviewers() {
+		let server = init_mock_server().await;
+		let requestx_api = Arc::new(InMemoryRequestXApi::default());
+		requestx_api
+			.make_requestx_api_level_request(LevelRequest {
+				discord_user_id: 555,
+				level_id: 97624039,
+				request_score: RequestRating::Four,
+				youtube_video_link: "https://youtu.be/dQw4w9WgXcQ".to_string(),
+				has_requested_feedback: true,
+				notify: false
+			})
+			.await
+			.unwrap();
+		requestx_api
+			.update_request_message_id(UpdateLevelRequestMessageId {
+				level_id: 97624039,
+				discord_message_id: 300,
+				discord_channel_id: test_guild_config().discord_request_channel_id
+			})
+			.await
+			.unwrap();
+		let discord_gateway = Arc::new(RecordingDiscordGateway::default());
+		let deferred_response_mock = mock_deferred_response(&server).await;
+		let submitted_response_mock = mock_edited_response(&server, "Review submitted").await;
+
+		run_review_modal(
+			&test_http(&server),
+			&Services::new(requestx_api.clone(), discord_gateway.clone()),
+			&modal_interaction(
+				&format!("{}:97624039", REVIEW_MODAL_CUSTOM_ID_PREFIX),
+				REVIEW_CONTENTS_INPUT_ID,
+				"Nice level",
+				json!([TEST_REVIEWER_ROLE_ID])
+			),
+			test_guild_config()
+		)
+		.await;
+
+		deferred_response_mock.assert_async().await;
+		submitted_response_mock.assert_async().await;
+		assert_eq!(
+			requestx_api
+				.get_level_review(GetLevelReview {
+					discord_user_id: TEST_USER_ID,
+					level_id: 97624039
+				})
+				.await
+				.unwrap()
+				.unwrap()
+				.review_contents,
+			"Nice level"
+		);
+		assert_eq!(
+			*discord_gateway.calls.lock().unwrap(),
+			vec![
+				DiscordGatewayCall::CreateThread {
+					channel_id: test_guild_config().discord_request_channel_id,
+					message_id: 300,
+					thread_id: 1000
+				},
+				DiscordGatewayCall::PostMessage {
+					channel_id: 1000,
+					message_id: 1001
+				}
+			]
+		);
+	}
+
+	#[tokio::test]
+	async fn review_modal_should_show_requestx_rejections_without_request_details() {
+		let server = init_mock_server().await;
+		server
+			.mock_async(|when, then| {
+				when.method(GET).path(format!(
+					"{}/97624039",
+					REQUESTX_API_CONFIG.paths.request_level
+				));
+				then.status(422).json_body(json!({
+					"code": "LEVEL_LOCKED",
+					"message": "This level can no longer be reviewed."
+				}));
+			})
+			.await;
+		let deferred_response_mock = mock_deferred_response(&server).await;
+		let rejected_response_mock = server
+			.mock_async(|when, then| {
+				when.method(PATCH)
+					.path(format!(
+						"/api/v10/webhooks/{}/interaction-token/messages/@original",
+						APP_CONFIG.client_config.discord_app_id
+					))
+					.json_body_partial(r#"{"content": "This level can no longer be reviewed."}"#);
+				then.status(200).json_body(discord_message(
+					test_guild_config().discord_request_channel_id,
+					2
+				));
+			})
+			.await;
+
+		run_review_modal(
+			&test_http(&server),
+			&test_services(&server),
+			&modal_interaction(
+				&format!("{}:97624039", REVIEW_MODAL_CUSTOM_ID_PREFIX),
+				REVIEW_CONTENTS_INPUT_ID,
+				"Nice level",
+				json!([TEST_REVIEWER_ROLE_ID])
+			),
+			test_guild_config()
+		)
+		.await;
+
+		deferred_response_mock.assert_async().await;
+		rejected_response_mock.assert_async().await;
+	}
+
+	#[tokio::test]
+	async fn review_modal_should_reject_submissions_from_non_reviewers() {
+		let server = init_mock_server().await;
+		let level_request_mock = server
+			.mock_async(|when, then| {
+				when.path_contains(&*REQUESTX_API_CONFIG.paths.request_level);
+				then.status(404);
+			})
+			.await;
+		let forbidden_response_mock = mock_interaction_response(&server, "Forbidden").await;
+
+		run_review_modal(
+			&test_http(&server),
+			&test_services(&server),
+			&modal_interaction(
+				&format!("{}:97624039", REVIEW_MODAL_CUSTOM_ID_PREFIX),
+				REVIEW_CONTENTS_INPUT_ID,
+				"Nice level",
+				json!([])
+			),
+			test_guild_config()
+		)
+		.await;
+
+		forbidden_response_mock.assert_async().await;
+		assert_eq!(level_request_mock.hits_async().await, 0);
+	}
 }
@@ -1,135 +1,330 @@
-use serenity::all::{
-	CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption,
-	MessageBuilder, ResolvedOption, ResolvedValue
-};
+use std::sync::Arc;
+
+use serenity::all::{CommandInteraction, CreateCommand, Http, UserId};
 
 use crate::{
-	service::reviewer_service::ReviewerService,
-	util::discord::{invoke_ephermal, log_to_discord}
+	config::client_config::GuildConfig,
+	model::{
+		audit_event::AuditEvent, discord_user::DiscordUser,
+		requestx_api::reviewer_data::ReviewerError
+	},
+	service::services::Services,
+	util::{
+		audit::audit,
+		command_options::{command_args, CommandOption},
+		discord::DeferredResponse
+	}
 };
 
+command_args! {
+	pub struct AddReviewerArgs {
+		user: UserId = CommandOption::user("user", "The user to grant the role of reviewer.")
+	}
+}
+
+command_args! {
+	pub struct RemoveReviewerArgs {
+		user: UserId = CommandOption::user("user", "The user to revoke the role of reviewer.")
+	}
+}
+
 pub fn register_add_reviewer() -> CreateCommand {
 	CreateCommand::new("add-reviewer")
 		.description("Adds a new level reviewer.")
-		.add_option(
-			CreateCommandOption::new(
-				CommandOptionType::User,
-				"user",
-				"The user to grant the role of reviewer."
-			)
-			.required(true)
-		)
+		.set_options(AddReviewerArgs::create_options())
 }
 
-pub async fn run_add_reviewer(ctx: &Context, command: &CommandInteraction) {
+pub async fn run_add_reviewer(
+	http: &Arc<Http>,
+	services: &Services,
+	command: &CommandInteraction,
+	guild_config: &GuildConfig
+) {
+	let response = DeferredResponse::defer_command(http, command).await;
 	let content: String;
-	let actor_user_id = command.user.id.get();
-	if actor_user_id != 164072941645070336 {
-		content = "Forbidden".to_string();
-		invoke_ephermal(&content, &ctx, &command).await;
-	} else {
-		let reviewer = &command.data.options();
-		if let Some(ResolvedOption {
-			value: ResolvedValue::User(user, _),
-			..
-		}) = reviewer.get(0)
+	let reviewer = AddReviewerArgs::parse(&command.data.options)
+		.ok()
+		.and_then(|args| command.data.resolved.users.get(&args.user));
+	if let Some(user) = reviewer {
+		match services
+			.reviewer_service
+			.create_reviewer(guild_config, &DiscordUser::from(user))
+			.await
 		{
-			let reviewer_service = ReviewerService::new();
-
-			match reviewer_service.create_reviewer(&ctx, &user).await {
-				Ok(()) => {
-					content = "User has been promoted to reviewer".to_string();
-					invoke_ephermal(&content, &ctx, &command).await;
-
-					{
-						let mut log_message = MessageBuilder::new();
-						log_message.push_bold(format!("{} ", &user.name));
-						log_message.push_line(format!("({}) has been promoted Reviewer", &user.id));
-						log_to_discord(log_message.build(), ctx.clone()).await
-					}
-				}
-				Err(error) => {
-					content = "Unable to add reviewer".to_string();
-					invoke_ephermal(&content, &ctx, &command).await;
-
-					{
-						let mut log_message = MessageBuilder::new();
-						log_message.push("Error promoting ".to_string());
-						log_message.push_bold(format!("{} ", &user.name));
-						log_message.push_line(format!("({}) to reviewer", &user.id));
-						log_message.push_codeblock(format!("{:?}", error), Some("rust"));
-						log_to_discord(log_message.build(), ctx.clone()).await
+			Ok(()) => {
+				content = "User has been promoted to reviewer".to_string();
+				response.edit(&content).await;
+
+				audit(
+					AuditEvent::ReviewerAdded {
+						admin: (&command.user).into(),
+						reviewer: user.into()
+					},
+					guild_config
+				)
+				.await
+			}
+			Err(error) => {
+				content = match &error {
+					ReviewerError::RequestX(requestx_error) => requestx_error.user_message(),
+					ReviewerError::Discord(_) => {
+						"The reviewer was registered, but the Discord role could not be granted."
+							.to_string()
 					}
-				}
+				};
+				response.edit(&content).await;
+
+				audit(
+					AuditEvent::Error {
+						user: (&command.user).into(),
+						action: format!("add-reviewer {}", user.id),
+						error: error.to_string()
+					},
+					guild_config
+				)
+				.await
 			}
-		} else {
-			content = "Unable to add reviewer".to_string();
-			invoke_ephermal(&content, &ctx, &command).await;
 		}
+	} else {
+		content = "Unable to add reviewer".to_string();
+		response.edit(&content).await;
 	}
 }
 
 pub fn register_remove_reviewer() -> CreateCommand {
 	CreateCommand::new("remove-reviewer")
 		.description("Removes the current level reviewer.")
-		.add_option(
-			CreateCommandOption::new(
-				CommandOptionType::User,
-				"user",
-				"The user to revoke the role of reviewer."
-			)
-			.required(true)
-		)
+		.set_options(RemoveReviewerArgs::create_options())
 }
 
-pub async fn run_remove_reviewer(ctx: &Context, command: &CommandInteraction) {
+pub async fn run_remove_reviewer(
+	http: &Arc<Http>,
+	services: &Services,
+	command: &CommandInteraction,
+	guild_config: &GuildConfig
+) {
+	let response = DeferredResponse::defer_command(http, command).await;
 	let content: String;
+	let reviewer = RemoveReviewerArgs::parse(&command.data.options)
+		.ok()
+		.and_then(|args| command.data.resolved.users.get(&args.user));
+	if let Some(user) = reviewer {
+		match services
+			.reviewer_service
+			.remove_reviewer(guild_config, &DiscordUser::from(user))
+			.await
+		{
+			Ok(()) => {
+				content = "User has been demoted from reviewer".to_string();
+				response.edit(&content).await;
+
+				audit(
+					AuditEvent::ReviewerRemoved {
+						admin: (&command.user).into(),
+						reviewer: user.into()
+					},
+					guild_config
+				)
+				.await
+			}
+			Err(error) => {
+				content = match &error {
+					ReviewerError::RequestX(requestx_error) => requestx_error.user_message(),
+					ReviewerError::Discord(_) => {
+						"The reviewer was removed, but the Discord role could not be revoked."
+							.to_string()
+					}
+				};
+				response.edit(&content).await;
 
-	let actor_user_id = command.user.id.get();
-	if actor_user_id != 164072941645070336 {
-		content = "Forbidden".to_string();
-		invoke_ephermal(&content, &ctx, &command).await;
+				audit(
+					AuditEvent::Error {
+						user: (&command.user).into(),
+						action: format!("remove-reviewer {}", user.id),
+						error: error.to_string()
+					},
+					guild_config
+				)
+				.await
+			}
+		}
 	} else {
-		let reviewer = &command.data.options();
-		if let Some(ResolvedOption {
-			value: ResolvedValue::User(user, _),
-			..
-		}) = reviewer.get(0)
-		{
-			let reviewer_service = ReviewerService::new();
-
-			match reviewer_service.remove_reviewer(&ctx, &user).await {
-				Ok(()) => {
-					content = "User has been demoted from reviewer".to_string();
-					invoke_ephermal(&content, &ctx, &command).await;
-
-					{
-						let mut log_message = MessageBuilder::new();
-						log_message.push_bold(format!("{} ", &user.name));
-						log_message
-							.push_line(format!("({}) has been demoted from Reviewer", &user.id));
-						log_to_discord(log_message.build(), ctx.clone()).await
+		content = "Unable to remove reviewer".to_string();
+		response.edit(&content).await;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use httpmock::Method::{DELETE, POST, PUT};
+	use serde_json::json;
+
+	use crate::{
+		commands::reviewer::{run_add_reviewer, run_remove_reviewer},
+		config::requestx_api_config::REQUESTX_API_CONFIG,
+		test_harness::{
+			command_interaction, init_mock_server, mock_deferred_response, mock_edited_response,
+			test_guild_config, test_http, test_services
+		}
+	};
+
+	#[tokio::test]
+	async fn add_reviewer_should_register_reviewer_and_grant_role() {
+		let server = init_mock_server().await;
+		let add_reviewer_mock = server
+			.mock_async(|when, then| {
+				when.method(POST)
+					.path(&*REQUESTX_API_CONFIG.paths.reviewer)
+					.json_body_partial(r#"{"reviewer_discord_id": 555}"#);
+				then.status(201);
+			})
+			.await;
+		let add_role_mock = server
+			.mock_async(|when, then| {
+				when.method(PUT).path(format!(
+					"/api/v10/guilds/{}/members/555/roles/{}",
+					test_guild_config().discord_guild_id,
+					test_guild_config().discord_reviewer_role_id
+				));
+				then.status(204);
+			})
+			.await;
+		let deferred_response_mock = mock_deferred_response(&server).await;
+		let interaction_response_mock =
+			mock_edited_response(&server, "User has been promoted to reviewer").await;
+		let command = command_interaction(
+			"add-reviewer",
+			json!([{ "name": "user", "type": 6, "value": "555" }]),
+			json!({
+				"users": {
+					"555": {
+						"id": "555",
+						"username": "somereviewer",
+						"discriminator": "0",
+						"global_name": null,
+						"avatar": null
 					}
 				}
-				Err(error) => {
-					content = "Unable to remove reviewer".to_string();
-					invoke_ephermal(&content, &ctx, &command).await;
-
-					{
-						{
-							let mut log_message = MessageBuilder::new();
-							log_message.push("Error demoting ".to_string());
-							log_message.push_bold(format!("{} ", &user.name));
-							log_message.push_line(format!("({}) from reviewer", &user.id));
-							log_message.push_codeblock(format!("{:?}", error), Some("rust"));
-							log_to_discord(log_message.build(), ctx.clone()).await
+			})
+		);
+
+		run_add_reviewer(
+			&test_http(&server),
+			&test_services(&server),
+			&command,
+			test_guild_config()
+		)
+		.await;
+
+		add_reviewer_mock.assert_async().await;
+		add_role_mock.assert_async().await;
+		deferred_response_mock.assert_async().await;
+		interaction_response_mock.assert_async().await;
+	}
+
+	#[tokio::test]
+	async fn add_reviewer_should_report_role_failures_as_discord_errors() {
+		let server = init_mock_server().await;
+		server
+			.mock_async(|when, then| {
+				when.method(POST).path(&*REQUESTX_API_CONFIG.paths.reviewer);
+				then.status(201);
+			})
+			.await;
+		server
+			.mock_async(|when, then| {
+				when.method(PUT).path(format!(
+					"/api/v10/guilds/{}/members/555/roles/{}",
+					test_guild_config().discord_guild_id,
+					test_guild_config().discord_reviewer_role_id
+				));
+				then.status(403)
+					.json_body(json!({ "code": 50013, "message": "Missing Permissions" }));
+			})
+			.await;
+		let deferred_response_mock = mock_deferred_response(&server).await;
+		let interaction_response_mock = mock_edited_response(
+			&server,
+			"The reviewer was registered, but the Discord role could not be granted."
+		)
+		.await;
+
+		run_add_reviewer(
+			&test_http(&server),
+			&test_services(&server),
+			&command_interaction(
+				"add-reviewer",
+				json!([{ "name": "user", "type": 6, "value": "555" }]),
+				json!({
+					"users": {
+						"555": {
+							"id": "555",
+							"username": "somereviewer",
+							"discriminator": "0",
+							"global_name": null,
+							"avatar": null
 						}
 					}
+				})
+			),
+			test_guild_config()
+		)
+		.await;
+
+		deferred_response_mock.assert_async().await;
+		interaction_response_mock.assert_async().await;
+	}
+
+	#[tokio::test]
+	async fn remove_reviewer_should_unregister_reviewer_and_revoke_role() {
+		let server = init_mock_server().await;
+		let remove_reviewer_mock = server
+			.mock_async(|when, then| {
+				when.method(DELETE)
+					.path(format!("{}/555", REQUESTX_API_CONFIG.paths.reviewer));
+				then.status(204);
+			})
+			.await;
+		let remove_role_mock = server
+			.mock_async(|when, then| {
+				when.method(DELETE).path(format!(
+					"/api/v10/guilds/{}/members/555/roles/{}",
+					test_guild_config().discord_guild_id,
+					test_guild_config().discord_reviewer_role_id
+				));
+				then.status(204);
+			})
+			.await;
+		let deferred_response_mock = mock_deferred_response(&server).await;
+		let interaction_response_mock =
+			mock_edited_response(&server, "User has been demoted from reviewer").await;
+		let command = command_interaction(
+			"remove-reviewer",
+			json!([{ "name": "user", "type": 6, "value": "555" }]),
+			json!({
+				"users": {
+					"555": {
+						"id": "555",
+						"username": "somereviewer",
+						"discriminator": "0",
+						"global_name": null,
+						"avatar": null
+					}
 				}
-			}
-		} else {
-			content = "Unable to remove reviewer".to_string();
-			invoke_ephermal(&content, &ctx, &command).await;
-		}
+			})
+		);
+
+		run_remove_reviewer(
+			&test_http(&server),
+			&test_services(&server),
+			&command,
+			test_guild_config()
+		)
+		.await;
+
+		remove_reviewer_mock.assert_async().await;
+		remove_role_mock.assert_async().await;
+		deferred_response_mock.assert_async().await;
+		interaction_response_mock.assert_async().await;
 	}
 }
@@ -1,192 +1,250 @@
-use std::str::FromStr;
+use std::sync::Arc;
 
 use log::error;
-use serenity::all::{
-	ChannelId, CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption,
-	Mentionable, MessageBuilder, UserId
-};
+use serenity::all::{CommandInteraction, CreateCommand, Http};
 
 use crate::{
+	commands::request_level::REQUEST_RATING_CHOICES,
+	config::client_config::GuildConfig,
 	model::{
+		audit_event::AuditEvent,
+		discord_user::DiscordUser,
+		error::command_option_error::CommandOptionError,
 		moderator::{Moderator, SuggestedRating, SuggestedScore},
-		request_score::LevelLength,
 		requestx_api::moderator_data::ModeratorError
 	},
-	service::moderator_service::ModeratorService,
-	util::discord::{invoke_ephermal, log_to_discord}
+	service::services::Services,
+	util::{
+		audit::audit,
+		command_options::{command_args, CommandOption},
+		discord::DeferredResponse
+	}
 };
 
+pub const SUGGESTED_RATING_CHOICES: [(&str, &str); 5] = [
+	("Rate", "Rate"),
+	("Feature", "Feature"),
+	("Epic", "Epic"),
+	("Legendary", "Legendary"),
+	("Mythic", "Mythic")
+];
+
+command_args! {
+	pub struct SendLevelArgs {
+		level_id: u64 = CommandOption::unsigned_integer(
+			"level-id",
+			"The level ID of the request to send"
+		)
+		.autocomplete(),
+		suggested_score: SuggestedScore = CommandOption::choice(
+			"suggested-score",
+			"The suggested amount of Stars/Moons this level should reward",
+			&[&[("No Send", "NoRate")], &REQUEST_RATING_CHOICES[..]].concat()
+		),
+		suggested_rating: SuggestedRating = CommandOption::choice(
+			"suggested-rating",
+			"The suggested Feature score this level should have",
+			&SUGGESTED_RATING_CHOICES
+		)
+	}
+}
+
 pub fn register_send_level() -> CreateCommand {
 	CreateCommand::new("send-level")
 		.description("Concludes a level request by either sending the level or not")
-		.add_option(
-			CreateCommandOption::new(
-				CommandOptionType::Integer,
-				"level-id",
-				"The level ID of the request to send"
-			)
-			.required(true)
-		)
-		.add_option(
-			CreateCommandOption::new(
-				CommandOptionType::String,
-				"suggested-score",
-				"The suggested amount of Stars/Moons this level should reward"
-			)
-			.required(true)
-			.add_string_choice("No Send", "NoRate")
-			.add_string_choice("Auto, 1 Star/Moon", "One")
-			.add_string_choice("Easy, 2 Stars/Moons", "Two")
-			.add_string_choice("Normal, 3 Stars/Moons", "Three")
-			.add_string_choice("Hard, 4 Stars/Moons", "Four")
-			.add_string_choice("Hard, 5 Stars/Moons", "Five")
-			.add_string_choice("Harder, 6 Stars/Moons", "Six")
-			.add_string_choice("Harder, 7 Stars/Moons", "Seven")
-			.add_string_choice("Insane, 8 Stars/Moons", "Eight")
-			.add_string_choice("Insane, 9 Stars/Moons", "Nine")
-			.add_string_choice("Demon, 10 Stars/Moons", "Ten")
-		)
-		.add_option(
-			CreateCommandOption::new(
-				CommandOptionType::String,
-				"suggested-rating",
-				"The suggested Feature score this level should have"
-			)
-			.required(true)
-			.add_string_choice("Rate", "Rate")
-			.add_string_choice("Feature", "Feature")
-			.add_string_choice("Epic", "Epic")
-			.add_string_choice("Legendary", "Legendary")
-			.add_string_choice("Mythic", "Mythic")
-		)
+		.set_options(SendLevelArgs::create_options())
 }
 
-pub async fn run_send_level(ctx: &Context, command: &CommandInteraction) {
-	let level_id = command
-		.data
-		.options
-		.get(0)
-		.unwrap()
-		.value
-		.as_i64()
-		.unwrap()
-		.unsigned_abs();
-	let suggested_score =
-		SuggestedScore::from_str(command.data.options.get(1).unwrap().value.as_str().unwrap())
-			.unwrap();
-	let suggested_rating =
-		SuggestedRating::from_str(command.data.options.get(2).unwrap().value.as_str().unwrap())
-			.unwrap();
-	let send_level_request = Moderator {
-		level_id,
-		suggested_score,
-		suggested_rating
+pub async fn run_send_level(
+	http: &Arc<Http>,
+	services: &Services,
+	command: &CommandInteraction,
+	guild_config: &GuildConfig
+) {
+	let response = DeferredResponse::defer_command(http, command).await;
+	let send_level_request = match parse_send_level_request(command) {
+		Ok(send_level_request) => send_level_request,
+		Err(error) => {
+			error!("Invalid send-level options: {}", error);
+			response.edit(&error.to_string()).await;
+			return;
+		}
 	};
-	let service = ModeratorService::new();
-	let content;
+	let content = send_level(
+		services,
+		&DiscordUser::from(&command.user),
+		guild_config,
+		send_level_request
+	)
+	.await;
+	response.edit(&content).await;
+}
+
+fn parse_send_level_request(command: &CommandInteraction) -> Result<Moderator, CommandOptionError> {
+	let args = SendLevelArgs::parse(&command.data.options)?;
+	Ok(Moderator {
+		level_id: args.level_id,
+		suggested_score: args.suggested_score,
+		suggested_rating: args.suggested_rating
+	})
+}
 
-	match service.send_level(&ctx, &command, send_level_request).await {
+pub async fn send_level(
+	services: &Services,
+	moderator: &DiscordUser,
+	guild_config: &GuildConfig,
+	send_level_request: Moderator
+) -> String {
+	match services
+		.moderator_service
+		.send_level(moderator, guild_config, send_level_request)
+		.await
+	{
 		Ok(level_request_data) => {
-			let mut send_level_message = MessageBuilder::new();
-			send_level_message.push(format!(
-				"\"{}\" ({}) ",
-				level_request_data.level_name, level_request_data.level_id
-			));
-			if level_request_data.level_length == LevelLength::Platformer {
-				if send_level_request.suggested_score == SuggestedScore::NoRate {
-					send_level_message.push_bold("has not ");
-					send_level_message.push("been sent...");
-				} else {
-					send_level_message.push_bold("has ");
-					send_level_message.push("been sent for ");
-					send_level_message.push_bold(format!(
-						"{}, {} Moons!",
-						serde_json::to_string(&suggested_rating)
-							.unwrap()
-							.replace("\"", ""),
-						serde_json::to_string(&suggested_score)
-							.unwrap()
-							.replace("\"", "")
-					));
+			let audit_event = if send_level_request.suggested_score == SuggestedScore::NoRate {
+				AuditEvent::LevelNotSent {
+					moderator: moderator.into(),
+					level_id: level_request_data.level_id,
+					level_name: level_request_data.level_name.clone()
 				}
 			} else {
-				if send_level_request.suggested_score == SuggestedScore::NoRate {
-					send_level_message.push_bold("has not ");
-					send_level_message.push("been sent...");
-				} else {
-					send_level_message.push_bold("has ");
-					send_level_message.push("been sent for ");
-					send_level_message.push_bold(format!(
-						"{}, {} Stars!",
-						serde_json::to_string(&suggested_rating)
-							.unwrap()
-							.replace("\"", ""),
-						serde_json::to_string(&suggested_score)
-							.unwrap()
-							.replace("\"", "")
-					));
+				AuditEvent::LevelSent {
+					moderator: moderator.into(),
+					level_id: level_request_data.level_id,
+					level_name: level_request_data.level_name.clone(),
+					suggested_score: send_level_request.suggested_score,
+					suggested_rating: send_level_request.suggested_rating
 				}
-			}
+			};
+			audit(audit_event, guild_config).await;
 
-			if level_request_data.notify {
-				send_level_message.push_line("");
-				send_level_message.push_line(format!(
-					"{}",
-					UserId::new(level_request_data.discord_id).mention()
-				));
-			}
-
-			match ChannelId::new(level_request_data.discord_thread_id.unwrap())
-				.say(&ctx.http, &send_level_message.build())
+			match services
+				.moderator_service
+				.post_send_level(guild_config, &level_request_data, &send_level_request)
 				.await
 			{
-				Ok(_msg) => {
-					content = "Level has been sent!".to_string();
-					invoke_ephermal(&content, &ctx, &command).await;
-
-					{
-						let mut log_message = MessageBuilder::new();
-						log_message.push_line("Level has been sent to RobTop".to_string());
-						log_message
-							.push_codeblock(format!("{:?}", level_request_data), Some("rust"));
-						log_message
-							.push_codeblock(format!("{:?}", send_level_request), Some("rust"));
-						log_to_discord(log_message.build(), ctx.clone()).await
-					}
+				Ok(_message_id) if send_level_request.suggested_score == SuggestedScore::NoRate => {
+					"Level marked as not sent.".to_string()
 				}
+				Ok(_message_id) => "Level has been sent!".to_string(),
 				Err(error) => {
 					error!("{}", error);
-					content = "Error sending message.".to_string();
-					invoke_ephermal(&content, &ctx, &command).await;
+					"Error sending message.".to_string()
 				}
 			}
 		}
 		Err(send_level_error) => {
+			audit(
+				AuditEvent::Error {
+					user: moderator.into(),
+					action: format!("send-level {}", send_level_request.level_id),
+					error: send_level_error.to_string()
+				},
+				guild_config
+			)
+			.await;
+
 			match send_level_error {
 				ModeratorError::LevelRequestDoesNotExist => {
-					content = "Level request does not exist.".to_string();
-					invoke_ephermal(&content, &ctx, &command).await;
+					"Level request does not exist.".to_string()
 				}
-				ModeratorError::RequestXApiError => {
-					content = "There was an error making the request".to_string();
-					invoke_ephermal(&content, &ctx, &command).await;
-				}
-				ModeratorError::SerializeError => {
-					content = "Unable to serialize request".to_string();
-					invoke_ephermal(&content, &ctx, &command).await;
-				}
-				ModeratorError::RequestError => {
-					content = "There was an error making the request".to_string();
-					invoke_ephermal(&content, &ctx, &command).await;
+				ModeratorError::RequestX(requestx_error) => requestx_error.user_message(),
+				ModeratorError::Discord(_) => {
+					"The verdict was saved, but the request thread could not be created on Discord."
+						.to_string()
 				}
 			}
+		}
+	}
+}
 
-			{
-				let mut log_message = MessageBuilder::new();
-				log_message.push_line("Unable to send level to RobTop".to_string());
-				log_message.push_codeblock(format!("{:?}", send_level_request), Some("rust"));
-				log_to_discord(log_message.build(), ctx.clone()).await
-			}
+#[cfg(test)]
+mod tests {
+	use httpmock::Method::{PATCH, POST};
+	use serde_json::json;
+
+	use crate::{
+		commands::send_level::run_send_level,
+		config::requestx_api_config::REQUESTX_API_CONFIG,
+		test_harness::{
+			command_interaction, init_mock_server, mock_create_message, mock_deferred_response,
+			mock_edited_response, test_guild_config, test_http, test_services, TEST_USER_ID
 		}
+	};
+
+	#[tokio::test]
+	async fn send_level_should_create_thread_and_post_suggestion() {
+		let server = init_mock_server().await;
+		let request_channel_id = test_guild_config().discord_request_channel_id;
+		let send_level_mock = server
+			.mock_async(|when, then| {
+				when.method(POST)
+					.path(&*REQUESTX_API_CONFIG.paths.send_level)
+					.json_body_partial(r#"{"level_id": 97624039}"#);
+				then.status(200).json_body(json!({
+					"level_id": 97624039,
+					"discord_id": TEST_USER_ID,
+					"discord_message_id": 300,
+					"discord_thread_id": null,
+					"level_name": "Some Level",
+					"level_author": "Some Author",
+					"request_score": "Four",
+					"level_length": "Short",
+					"youtube_video_link": "https://youtu.be/dQw4w9WgXcQ",
+					"has_requested_feedback": false,
+					"notify": false
+				}));
+			})
+			.await;
+		let create_thread_mock = server
+			.mock_async(|when, then| {
+				when.method(POST).path(format!(
+					"/api/v10/channels/{}/messages/300/threads",
+					request_channel_id
+				));
+				then.status(201).json_body(json!({
+					"id": "400",
+					"type": 11,
+					"guild_id": test_guild_config().discord_guild_id.to_string(),
+					"parent_id": request_channel_id.to_string(),
+					"name": "\"Some Level\" (97624039)"
+				}));
+			})
+			.await;
+		let update_thread_id_mock = server
+			.mock_async(|when, then| {
+				when.method(PATCH)
+					.path(&*REQUESTX_API_CONFIG.paths.update_request_thread_id)
+					.json_body_partial(r#"{"level_id": 97624039, "discord_thread_id": 400}"#);
+				then.status(200);
+			})
+			.await;
+		let thread_message_mock = mock_create_message(&server, 400, 500).await;
+		let deferred_response_mock = mock_deferred_response(&server).await;
+		let interaction_response_mock = mock_edited_response(&server, "Level has been sent!").await;
+		let command = command_interaction(
+			"send-level",
+			json!([
+				{ "name": "level-id", "type": 4, "value": 97624039 },
+				{ "name": "suggested-score", "type": 3, "value": "Four" },
+				{ "name": "suggested-rating", "type": 3, "value": "Feature" }
+			]),
+			json!({})
+		);
+
+		run_send_level(
+			&test_http(&server),
+			&test_services(&server),
+			&command,
+			test_guild_config()
+		)
+		.await;
+
+		send_level_mock.assert_async().await;
+		create_thread_mock.assert_async().await;
+		update_thread_id_mock.assert_async().await;
+		thread_message_mock.assert_async().await;
+		deferred_response_mock.assert_async().await;
+		interaction_response_mock.assert_async().await;
 	}
 }
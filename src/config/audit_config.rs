@@ -0,0 +1,13 @@
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+
+use crate::config::common_config::APP_CONFIG;
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct AuditConfig {
+	pub jsonl_path: Option<String>
+}
+
+lazy_static! {
+	pub static ref AUDIT_CONFIG: &'static AuditConfig = &APP_CONFIG.audit_config;
+}
@@ -1,16 +1,78 @@
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 
-use crate::config::common_config::APP_CONFIG;
+use crate::{
+	config::common_config::APP_CONFIG,
+	model::{request_score::LevelLength, requestx_api::level_request_data::LevelRequestData}
+};
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ClientConfig {
 	pub discord_bot_token: String,
 	pub discord_app_id: u64,
+	pub guilds: Vec<GuildConfig>
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GuildConfig {
 	pub discord_guild_id: u64,
+	pub discord_request_channel_id: u64,
+	#[serde(default)]
+	pub discord_level_length_channel_ids: LevelLengthChannelIds,
 	pub discord_reviewer_role_id: u64,
-	pub discord_bot_admin_id: u64,
-	pub discord_log_channel_id: u64
+	pub discord_log_channel_id: u64,
+	pub discord_bot_admin_ids: Vec<u64>,
+	#[serde(default)]
+	pub discord_admin_role_ids: Vec<u64>,
+	#[serde(default)]
+	pub discord_moderator_ids: Vec<u64>,
+	#[serde(default)]
+	pub discord_moderator_role_ids: Vec<u64>,
+	#[serde(default)]
+	pub discord_requester_role_ids: Vec<u64>,
+	#[serde(default)]
+	pub discord_plain_text_messages: bool
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct LevelLengthChannelIds {
+	pub tiny: Option<u64>,
+	pub short: Option<u64>,
+	pub medium: Option<u64>,
+	pub long: Option<u64>,
+	pub extra_long: Option<u64>,
+	pub platformer: Option<u64>
+}
+
+impl ClientConfig {
+	pub fn get_guild_config(&self, discord_guild_id: u64) -> Option<&GuildConfig> {
+		self.guilds
+			.iter()
+			.find(|guild_config| guild_config.discord_guild_id == discord_guild_id)
+	}
+}
+
+impl GuildConfig {
+	pub fn get_request_channel_id(&self, level_length: LevelLength) -> u64 {
+		let channel_ids = &self.discord_level_length_channel_ids;
+		match level_length {
+			LevelLength::Tiny => channel_ids.tiny,
+			LevelLength::Short => channel_ids.short,
+			LevelLength::Medium => channel_ids.medium,
+			LevelLength::Long => channel_ids.long,
+			LevelLength::ExtraLong => channel_ids.extra_long,
+			LevelLength::Platformer => channel_ids.platformer
+		}
+		.unwrap_or(self.discord_request_channel_id)
+	}
+
+	// Requests stay in the channel they were posted to even if the channel
+	// configuration changes afterwards, older requests never stored it
+	pub fn get_level_request_channel_id(&self, level_request: &LevelRequestData) -> u64 {
+		level_request
+			.discord_channel_id
+			.unwrap_or_else(|| self.get_request_channel_id(level_request.level_length))
+	}
 }
 
 lazy_static! {
@@ -5,14 +5,17 @@ use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 
 use crate::config::{
-	auth_config::AuthConfig, client_config::ClientConfig, requestx_api_config::RequestxApiConfig
+	audit_config::AuditConfig, auth_config::AuthConfig, client_config::ClientConfig,
+	requestx_api_config::RequestxApiConfig
 };
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct AppConfig {
 	pub client_config: ClientConfig,
 	pub requestx_api_config: RequestxApiConfig,
-	pub auth_config: AuthConfig
+	pub auth_config: AuthConfig,
+	#[serde(default)]
+	pub audit_config: AuditConfig
 }
 
 pub fn init_app_config() -> Result<AppConfig, ConfigError> { read_app_config() }
@@ -22,13 +25,10 @@ fn read_app_config() -> Result<AppConfig, ConfigError> {
 	let mut settings = Config::builder();
 
 	let handlebars = handlebars::Handlebars::new();
-	let template_string;
-	if cfg!(test) {
-		template_string =
-			fs::read_to_string("Config_test.toml").expect("Unable to open configuration file");
+	let template_string = if cfg!(test) {
+		fs::read_to_string("Config_test.toml").expect("Unable to open configuration file")
 	} else {
-		template_string =
-			fs::read_to_string("Config.toml").expect("Unable to open configuration file");
+		fs::read_to_string("Config.toml").expect("Unable to open configuration file")
 	};
 
 	let rendered = handlebars
@@ -1,4 +1,4 @@
-pub static CONTENT_TYPE: &'static str = "Content-Type";
-pub static CONTENT_LENGTH: &'static str = "Content-Length";
-pub static APPLICATION_JSON: &'static str = "application/json";
-pub static YOUTUBE_LINK_REGEX: &'static str = "^((?:https?:)?\\/\\/)?((?:www|m)\\.)?((?:youtube(-nocookie)?\\.com|youtu.be))(\\/(?:[\\w\\-]+\\?v=|embed\\/|v\\/)?)([\\w\\-]+)(\\S+)?$";
+pub static CONTENT_TYPE: &str = "Content-Type";
+pub static CONTENT_LENGTH: &str = "Content-Length";
+pub static APPLICATION_JSON: &str = "application/json";
+pub static YOUTUBE_LINK_REGEX: &str = "^((?:https?:)?\\/\\/)?((?:www|m)\\.)?((?:youtube(-nocookie)?\\.com|youtu.be))(\\/(?:[\\w\\-]+\\?v=|embed\\/|v\\/)?)([\\w\\-]+)(\\S+)?$";
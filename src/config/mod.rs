@@ -1,3 +1,4 @@
+pub mod audit_config;
 pub mod auth_config;
 pub mod client_config;
 pub mod common_config;
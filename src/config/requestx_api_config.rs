@@ -1,3 +1,5 @@
+use std::{collections::HashMap, time::Duration};
+
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 
@@ -7,7 +9,9 @@ use crate::config::common_config::APP_CONFIG;
 pub struct RequestxApiConfig {
 	pub base_url: String,
 	pub paths: RequestxApiConfigPaths,
-	pub headers: RequestxApiHeaders
+	pub headers: RequestxApiHeaders,
+	#[serde(default)]
+	pub policy: RequestxApiPolicy
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -27,6 +31,49 @@ pub struct RequestxApiHeaders {
 	pub requestx_discord_app_id: String
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RequestxApiPolicy {
+	pub timeout_ms: u64,
+	// Keyed by the name of the path in `requestx_api_config.paths`
+	pub endpoint_timeout_ms: HashMap<String, u64>,
+	pub max_retries: u32,
+	pub retry_base_delay_ms: u64,
+	pub circuit_breaker_failure_threshold: u32,
+	pub circuit_breaker_reset_secs: u64
+}
+
+impl RequestxApiPolicy {
+	pub fn get_timeout(&self, endpoint: &str) -> Duration {
+		Duration::from_millis(
+			*self
+				.endpoint_timeout_ms
+				.get(endpoint)
+				.unwrap_or(&self.timeout_ms)
+		)
+	}
+
+	pub fn get_retry_delay(&self, attempt: u32) -> Duration {
+		Duration::from_millis(
+			self.retry_base_delay_ms
+				.saturating_mul(2u64.saturating_pow(attempt.saturating_sub(1)))
+		)
+	}
+}
+
+impl Default for RequestxApiPolicy {
+	fn default() -> Self {
+		RequestxApiPolicy {
+			timeout_ms: 10000,
+			endpoint_timeout_ms: HashMap::new(),
+			max_retries: 3,
+			retry_base_delay_ms: 250,
+			circuit_breaker_failure_threshold: 5,
+			circuit_breaker_reset_secs: 30
+		}
+	}
+}
+
 lazy_static! {
 	pub static ref REQUESTX_API_CONFIG: &'static RequestxApiConfig =
 		&APP_CONFIG.requestx_api_config;
@@ -4,14 +4,29 @@ mod model;
 
 mod serenity;
 mod service;
+#[cfg(test)]
+mod test_harness;
 mod util;
 
-use std::process;
+use std::{env, path::PathBuf, process, sync::Arc};
 
 use ::serenity::{prelude::GatewayIntents, Client};
-use log::error;
+use log::{error, info};
 
-use crate::config::common_config::{init_app_config, APP_CONFIG};
+use crate::{
+	config::common_config::{init_app_config, APP_CONFIG},
+	model::{
+		discord_gateway::serenity_discord_gateway::SerenityDiscordGateway,
+		requestx_api::{
+			in_memory_requestx_api::InMemoryRequestXApi, requestx_api_client::RequestXApiClient,
+			requestx_api_trait::RequestXApi
+		}
+	},
+	service::{auth_service::JWT, services::Services},
+	util::discord_log_sink::{DiscordLogSink, DISCORD_LOG_SINK}
+};
+
+const DEFAULT_FAKE_BACKEND_PATH: &str = "fake_backend.json";
 
 #[tokio::main]
 async fn main() {
@@ -20,6 +35,11 @@ async fn main() {
 		error!("Error loading app config: {}", error);
 		process::exit(1)
 	} else {
+		let requestx_api = match get_fake_backend_path() {
+			Some(fake_backend_path) => create_fake_backend(fake_backend_path),
+			None => create_requestx_api_client().await
+		};
+
 		let mut client = Client::builder(
 			&APP_CONFIG.client_config.discord_bot_token,
 			GatewayIntents::empty()
@@ -27,8 +47,76 @@ async fn main() {
 		.event_handler(serenity::command_interaction_handler::Handler)
 		.await
 		.expect("Error creating client");
+		// The gateway shares the client's HTTP instance so its rate limits apply
+		let discord_gateway = Arc::new(SerenityDiscordGateway::new(client.http.clone()));
+		client
+			.data
+			.write()
+			.await
+			.insert::<Services>(Arc::new(Services::new(requestx_api, discord_gateway)));
+		let log_sink = DISCORD_LOG_SINK.get_or_init(|| DiscordLogSink::start(client.http.clone()));
+
+		let shard_manager = client.shard_manager.clone();
+		tokio::spawn(async move {
+			if let Err(error) = tokio::signal::ctrl_c().await {
+				error!("Unable to listen for shutdown signal: {}", error);
+				return;
+			}
+			info!("Shutting down");
+			shard_manager.shutdown_all().await;
+		});
+
 		if let Err(why) = client.start().await {
 			error!("Client error: {why:?}");
 		}
+		log_sink.shutdown().await;
+	}
+}
+
+// `--fake-backend [path]` runs the bot against an in-process RequestX backend
+// so the request, review and send flow can be tried without a server
+fn get_fake_backend_path() -> Option<PathBuf> {
+	let args: Vec<String> = env::args().collect();
+	let flag_index = args.iter().position(|arg| arg == "--fake-backend")?;
+	match args.get(flag_index + 1) {
+		Some(path) if !path.starts_with("--") => Some(PathBuf::from(path)),
+		_ => Some(PathBuf::from(DEFAULT_FAKE_BACKEND_PATH))
+	}
+}
+
+fn create_fake_backend(fake_backend_path: PathBuf) -> Arc<dyn RequestXApi> {
+	match InMemoryRequestXApi::with_persistence(fake_backend_path.clone()) {
+		Ok(fake_backend) => {
+			info!(
+				"Using the fake RequestX backend stored in {}",
+				fake_backend_path.display()
+			);
+			Arc::new(fake_backend)
+		}
+		Err(error) => {
+			error!(
+				"Unable to load fake backend state from {}: {}",
+				fake_backend_path.display(),
+				error
+			);
+			process::exit(1)
+		}
 	}
 }
+
+async fn create_requestx_api_client() -> Arc<dyn RequestXApi> {
+	// Refuse to connect to Discord when every command would fail to reach RequestX
+	match JWT.get_jwt().await {
+		Ok(_) => info!("Authenticated with RequestX"),
+		Err(error) => {
+			error!(
+				"Unable to authenticate with RequestX, check auth_config: {}",
+				error
+			);
+			process::exit(1)
+		}
+	}
+	JWT.start_refresh_task();
+
+	Arc::new(RequestXApiClient::new())
+}
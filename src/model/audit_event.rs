@@ -0,0 +1,124 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serenity::all::User;
+
+use crate::model::{
+	discord_user::DiscordUser,
+	moderator::{SuggestedRating, SuggestedScore},
+	permission::Permission,
+	request_score::{LevelLength, RequestRating}
+};
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct AuditUser {
+	pub id: u64,
+	pub name: String
+}
+
+impl From<&DiscordUser> for AuditUser {
+	fn from(user: &DiscordUser) -> Self {
+		AuditUser {
+			id: user.id,
+			name: user.name.clone()
+		}
+	}
+}
+
+impl From<&User> for AuditUser {
+	fn from(user: &User) -> Self {
+		AuditUser {
+			id: user.id.get(),
+			name: user.name.clone()
+		}
+	}
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "event")]
+pub enum AuditEvent {
+	LevelRequested {
+		requester: AuditUser,
+		level_id: u64,
+		level_name: String,
+		request_score: RequestRating,
+		level_length: LevelLength,
+		has_requested_feedback: bool
+	},
+	LevelRequestEdited {
+		requester: AuditUser,
+		level_id: u64,
+		level_name: String,
+		request_score: RequestRating,
+		has_requested_feedback: bool,
+		notify: bool
+	},
+	LevelRequestWithdrawn {
+		requester: AuditUser,
+		level_id: u64,
+		level_name: String
+	},
+	ReviewPosted {
+		reviewer: AuditUser,
+		level_id: u64
+	},
+	ReviewEdited {
+		reviewer: AuditUser,
+		level_id: u64
+	},
+	LevelSent {
+		moderator: AuditUser,
+		level_id: u64,
+		level_name: String,
+		suggested_score: SuggestedScore,
+		suggested_rating: SuggestedRating
+	},
+	LevelNotSent {
+		moderator: AuditUser,
+		level_id: u64,
+		level_name: String
+	},
+	ReviewerAdded {
+		admin: AuditUser,
+		reviewer: AuditUser
+	},
+	ReviewerRemoved {
+		admin: AuditUser,
+		reviewer: AuditUser
+	},
+	PermissionDenied {
+		user: AuditUser,
+		action: String,
+		required_permission: Permission
+	},
+	Error {
+		user: AuditUser,
+		action: String,
+		error: String
+	}
+}
+
+impl AuditEvent {
+	pub fn actor(&self) -> &AuditUser {
+		match self {
+			AuditEvent::LevelRequested { requester, .. }
+			| AuditEvent::LevelRequestEdited { requester, .. }
+			| AuditEvent::LevelRequestWithdrawn { requester, .. } => requester,
+			AuditEvent::ReviewPosted { reviewer, .. }
+			| AuditEvent::ReviewEdited { reviewer, .. } => reviewer,
+			AuditEvent::LevelSent { moderator, .. }
+			| AuditEvent::LevelNotSent { moderator, .. } => moderator,
+			AuditEvent::ReviewerAdded { admin, .. } | AuditEvent::ReviewerRemoved { admin, .. } => {
+				admin
+			}
+			AuditEvent::PermissionDenied { user, .. } | AuditEvent::Error { user, .. } => user
+		}
+	}
+}
+
+#[derive(Serialize, Debug)]
+pub struct AuditRecord {
+	pub timestamp: DateTime<Utc>,
+	pub guild_id: u64,
+	#[serde(flatten)]
+	pub event: AuditEvent
+}
@@ -0,0 +1,58 @@
+use async_trait::async_trait;
+
+use crate::{
+	config::client_config::GuildConfig, model::error::discord_gateway_error::DiscordGatewayError,
+	util::render::RenderedMessage
+};
+
+// Everything the services need from Discord, so the review and send workflows
+// can be exercised without a serenity client
+#[async_trait]
+pub trait DiscordGateway: Send + Sync {
+	async fn post_message(
+		&self,
+		channel_id: u64,
+		message: &RenderedMessage,
+		guild_config: &GuildConfig
+	) -> Result<u64, DiscordGatewayError>;
+
+	async fn edit_message(
+		&self,
+		channel_id: u64,
+		message_id: u64,
+		message: &RenderedMessage,
+		guild_config: &GuildConfig
+	) -> Result<(), DiscordGatewayError>;
+
+	async fn delete_message(
+		&self,
+		channel_id: u64,
+		message_id: u64
+	) -> Result<(), DiscordGatewayError>;
+
+	async fn delete_thread(&self, thread_id: u64) -> Result<(), DiscordGatewayError>;
+
+	async fn create_thread(
+		&self,
+		channel_id: u64,
+		message_id: u64,
+		name: String,
+		audit_log_reason: String
+	) -> Result<u64, DiscordGatewayError>;
+
+	async fn add_role(
+		&self,
+		guild_id: u64,
+		user_id: u64,
+		role_id: u64
+	) -> Result<(), DiscordGatewayError>;
+
+	async fn remove_role(
+		&self,
+		guild_id: u64,
+		user_id: u64,
+		role_id: u64
+	) -> Result<(), DiscordGatewayError>;
+
+	fn mention_user(&self, user_id: u64) -> String;
+}
@@ -0,0 +1,2 @@
+pub mod discord_gateway_trait;
+pub mod serenity_discord_gateway;
@@ -0,0 +1,124 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serenity::all::{ChannelId, CreateThread, GuildId, Http, Mentionable, UserId};
+
+use crate::{
+	config::client_config::GuildConfig,
+	model::{
+		discord_gateway::discord_gateway_trait::DiscordGateway,
+		error::discord_gateway_error::DiscordGatewayError
+	},
+	util::render::RenderedMessage
+};
+
+pub struct SerenityDiscordGateway {
+	http: Arc<Http>
+}
+
+impl SerenityDiscordGateway {
+	pub fn new(http: Arc<Http>) -> Self { SerenityDiscordGateway { http } }
+}
+
+#[async_trait]
+impl DiscordGateway for SerenityDiscordGateway {
+	async fn post_message(
+		&self,
+		channel_id: u64,
+		message: &RenderedMessage,
+		guild_config: &GuildConfig
+	) -> Result<u64, DiscordGatewayError> {
+		let message = ChannelId::new(channel_id)
+			.send_message(&self.http, message.to_create_message(guild_config))
+			.await?;
+		Ok(message.id.get())
+	}
+
+	async fn edit_message(
+		&self,
+		channel_id: u64,
+		message_id: u64,
+		message: &RenderedMessage,
+		guild_config: &GuildConfig
+	) -> Result<(), DiscordGatewayError> {
+		ChannelId::new(channel_id)
+			.edit_message(
+				&self.http,
+				message_id,
+				message.to_edit_message(guild_config)
+			)
+			.await?;
+		Ok(())
+	}
+
+	async fn delete_message(
+		&self,
+		channel_id: u64,
+		message_id: u64
+	) -> Result<(), DiscordGatewayError> {
+		ChannelId::new(channel_id)
+			.delete_message(&self.http, message_id)
+			.await?;
+		Ok(())
+	}
+
+	async fn delete_thread(&self, thread_id: u64) -> Result<(), DiscordGatewayError> {
+		ChannelId::new(thread_id).delete(&self.http).await?;
+		Ok(())
+	}
+
+	async fn create_thread(
+		&self,
+		channel_id: u64,
+		message_id: u64,
+		name: String,
+		audit_log_reason: String
+	) -> Result<u64, DiscordGatewayError> {
+		let thread = ChannelId::new(channel_id)
+			.create_thread_from_message(
+				&self.http,
+				message_id,
+				CreateThread::new(name)
+					.audit_log_reason(&audit_log_reason)
+					.invitable(false)
+			)
+			.await?;
+		Ok(thread.id.get())
+	}
+
+	async fn add_role(
+		&self,
+		guild_id: u64,
+		user_id: u64,
+		role_id: u64
+	) -> Result<(), DiscordGatewayError> {
+		self.http
+			.add_member_role(
+				GuildId::new(guild_id),
+				UserId::new(user_id),
+				role_id.into(),
+				None
+			)
+			.await?;
+		Ok(())
+	}
+
+	async fn remove_role(
+		&self,
+		guild_id: u64,
+		user_id: u64,
+		role_id: u64
+	) -> Result<(), DiscordGatewayError> {
+		self.http
+			.remove_member_role(
+				GuildId::new(guild_id),
+				UserId::new(user_id),
+				role_id.into(),
+				None
+			)
+			.await?;
+		Ok(())
+	}
+
+	fn mention_user(&self, user_id: u64) -> String { UserId::new(user_id).mention().to_string() }
+}
@@ -0,0 +1,18 @@
+use serenity::all::User;
+
+// The parts of a Discord user the services need, so they can be driven without
+// a serenity User
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscordUser {
+	pub id: u64,
+	pub name: String
+}
+
+impl From<&User> for DiscordUser {
+	fn from(user: &User) -> Self {
+		DiscordUser {
+			id: user.id.get(),
+			name: user.name.clone()
+		}
+	}
+}
@@ -6,7 +6,8 @@ use std::{
 #[derive(Debug, PartialEq)]
 pub enum AuthError {
 	Unauthorized,
-	AuthenticationFailed
+	AuthenticationFailed,
+	InvalidToken
 }
 
 impl Display for AuthError {
@@ -16,7 +17,10 @@ impl Display for AuthError {
 				write!(f, "Unauthorized")
 			}
 			AuthError::AuthenticationFailed => {
-				write!(f, "There was an error while attempting to auhtenticate")
+				write!(f, "There was an error while attempting to authenticate")
+			}
+			AuthError::InvalidToken => {
+				write!(f, "The server did not return a valid authorization token")
 			}
 		}
 	}
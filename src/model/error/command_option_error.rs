@@ -0,0 +1,25 @@
+use std::{
+	error::Error,
+	fmt::{Display, Formatter}
+};
+
+#[derive(Debug, PartialEq)]
+pub enum CommandOptionError {
+	MissingOption(String),
+	InvalidOption(String)
+}
+
+impl Display for CommandOptionError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			CommandOptionError::MissingOption(option_name) => {
+				write!(f, "Missing option `{}`", option_name)
+			}
+			CommandOptionError::InvalidOption(option_name) => {
+				write!(f, "Invalid value for option `{}`", option_name)
+			}
+		}
+	}
+}
+
+impl Error for CommandOptionError {}
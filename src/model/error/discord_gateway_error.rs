@@ -0,0 +1,27 @@
+use std::{
+	error::Error,
+	fmt::{Display, Formatter}
+};
+
+#[derive(Debug, PartialEq)]
+pub enum DiscordGatewayError {
+	RequestFailed(String)
+}
+
+impl Display for DiscordGatewayError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			DiscordGatewayError::RequestFailed(reason) => {
+				write!(f, "Discord rejected the request: {}", reason)
+			}
+		}
+	}
+}
+
+impl Error for DiscordGatewayError {}
+
+impl From<serenity::Error> for DiscordGatewayError {
+	fn from(error: serenity::Error) -> Self {
+		DiscordGatewayError::RequestFailed(error.to_string())
+	}
+}
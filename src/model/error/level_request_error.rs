@@ -3,12 +3,13 @@ use std::{
 	fmt::{Display, Formatter}
 };
 
+use crate::model::error::requestx_error::{RequestXError, RequestXResponseError};
+
 #[derive(Debug, PartialEq)]
 pub enum LevelRequestError {
 	LevelRequestExists,
-	RequestError,
-	SerializeError,
-	RequestXApiError
+	InvalidVideoLink,
+	RequestX(RequestXError)
 }
 
 impl Display for LevelRequestError {
@@ -17,17 +18,22 @@ impl Display for LevelRequestError {
 			LevelRequestError::LevelRequestExists => {
 				write!(f, "Level has already been requested")
 			}
-			LevelRequestError::RequestError => {
-				write!(f, "Unable to make request to server")
-			}
-			LevelRequestError::SerializeError => {
-				write!(f, "Unable to serialize level request")
-			}
-			LevelRequestError::RequestXApiError => {
-				write!(f, "The server failed to make the level request")
+			LevelRequestError::InvalidVideoLink => write!(f, "Invalid video link"),
+			LevelRequestError::RequestX(requestx_error) => {
+				write!(f, "Level request failed: {}", requestx_error)
 			}
 		}
 	}
 }
 
 impl Error for LevelRequestError {}
+
+impl From<RequestXError> for LevelRequestError {
+	fn from(requestx_error: RequestXError) -> Self { LevelRequestError::RequestX(requestx_error) }
+}
+
+impl From<RequestXResponseError> for LevelRequestError {
+	fn from(response_error: RequestXResponseError) -> Self {
+		LevelRequestError::RequestX(response_error.into())
+	}
+}
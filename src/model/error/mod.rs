@@ -1,2 +1,5 @@
 pub mod auth_error;
+pub mod command_option_error;
+pub mod discord_gateway_error;
 pub mod level_request_error;
+pub mod requestx_error;
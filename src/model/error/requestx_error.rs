@@ -0,0 +1,133 @@
+use std::{
+	error::Error,
+	fmt::{Display, Formatter}
+};
+
+use reqwest::{Response, StatusCode};
+use serde::Deserialize;
+
+use crate::model::error::auth_error::AuthError;
+
+#[derive(Deserialize, Debug, PartialEq)]
+pub struct RequestXErrorBody {
+	pub code: String,
+	pub message: String
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RequestXResponseError {
+	pub status: StatusCode,
+	pub endpoint: String,
+	pub body: Option<RequestXErrorBody>
+}
+
+impl RequestXResponseError {
+	pub async fn from_response(response: Response) -> Self {
+		let status = response.status();
+		let endpoint = response.url().path().to_string();
+		let body = match response.text().await {
+			Ok(response_string) => serde_json::from_str(&response_string).ok(),
+			Err(_) => None
+		};
+
+		RequestXResponseError {
+			status,
+			endpoint,
+			body
+		}
+	}
+
+	// Prefer the server's explanation so users see why the request was rejected
+	pub fn message(&self) -> String {
+		match &self.body {
+			Some(body) => body.message.clone(),
+			None => "The server failed to handle the request".to_string()
+		}
+	}
+}
+
+impl Display for RequestXResponseError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match &self.body {
+			Some(body) => {
+				write!(
+					f,
+					"{} responded with {} ({}): {}",
+					self.endpoint, self.status, body.code, body.message
+				)
+			}
+			None => {
+				write!(f, "{} responded with {}", self.endpoint, self.status)
+			}
+		}
+	}
+}
+
+impl Error for RequestXResponseError {}
+
+// Failures any RequestX call can run into, the per-domain errors wrap this and
+// only add the cases specific to their endpoints
+#[derive(Debug, PartialEq)]
+pub enum RequestXError {
+	RequestError,
+	SerializeError,
+	DeserializeError,
+	Unavailable,
+	AuthError(AuthError),
+	ResponseError(RequestXResponseError)
+}
+
+impl RequestXError {
+	pub fn user_message(&self) -> String {
+		match self {
+			RequestXError::RequestError | RequestXError::SerializeError => {
+				"There was an error making the request.".to_string()
+			}
+			RequestXError::DeserializeError => {
+				"Unable to read the response from the server.".to_string()
+			}
+			RequestXError::Unavailable => {
+				"RequestX is currently unavailable, please try again later.".to_string()
+			}
+			RequestXError::AuthError(_) => "Unable to authenticate with RequestX.".to_string(),
+			RequestXError::ResponseError(response_error) => response_error.message()
+		}
+	}
+}
+
+impl Display for RequestXError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			RequestXError::RequestError => {
+				write!(f, "Unable to make request to server")
+			}
+			RequestXError::SerializeError => {
+				write!(f, "Unable to serialize request")
+			}
+			RequestXError::DeserializeError => {
+				write!(f, "Unable to deserialize response")
+			}
+			RequestXError::Unavailable => {
+				write!(f, "RequestX is currently unavailable")
+			}
+			RequestXError::AuthError(auth_error) => {
+				write!(f, "Unable to authenticate with RequestX: {}", auth_error)
+			}
+			RequestXError::ResponseError(response_error) => {
+				write!(f, "{}", response_error)
+			}
+		}
+	}
+}
+
+impl Error for RequestXError {}
+
+impl From<RequestXResponseError> for RequestXError {
+	fn from(response_error: RequestXResponseError) -> Self {
+		RequestXError::ResponseError(response_error)
+	}
+}
+
+impl From<AuthError> for RequestXError {
+	fn from(auth_error: AuthError) -> Self { RequestXError::AuthError(auth_error) }
+}
@@ -1,12 +1,41 @@
 use serde::Serialize;
 
-use crate::model::request_score::RequestRating;
+use crate::model::request_score::{LevelLength, RequestRating};
 
 #[derive(Serialize)]
 pub struct GetLevelRequest {
 	pub level_id: u64
 }
 
+#[derive(Serialize, Debug, Copy, Clone)]
+pub struct GetLevelRequestQueue {
+	pub page: u64,
+	pub page_size: u64,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub request_rating: Option<RequestRating>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub level_length: Option<LevelLength>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub has_requested_feedback: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub max_age_hours: Option<u64>
+}
+
+#[derive(Serialize, Debug)]
+pub struct SearchLevelRequests {
+	pub page: u64,
+	pub page_size: u64,
+	pub search: String
+}
+
+#[derive(Serialize, Debug)]
+pub struct GetRequesterLevelRequests {
+	#[serde(rename = "discord_id")]
+	pub discord_user_id: u64,
+	pub page: u64,
+	pub page_size: u64
+}
+
 #[derive(Serialize)]
 pub struct GetLevelReview {
 	#[serde(rename = "discord_id")]
@@ -14,6 +43,11 @@ pub struct GetLevelReview {
 	pub level_id: u64
 }
 
+#[derive(Serialize)]
+pub struct GetLevelReviews {
+	pub level_id: u64
+}
+
 #[derive(Serialize, Debug)]
 pub struct LevelRequest {
 	#[serde(rename = "discord_id")]
@@ -26,12 +60,38 @@ pub struct LevelRequest {
 	pub notify: bool
 }
 
+// Unset fields are left unchanged
+#[derive(Serialize, Debug)]
+pub struct UpdateLevelRequest {
+	#[serde(skip_serializing)]
+	pub level_id: u64,
+	#[serde(rename = "discord_id")]
+	pub discord_user_id: u64,
+	#[serde(rename = "request_rating", skip_serializing_if = "Option::is_none")]
+	pub request_score: Option<RequestRating>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub youtube_video_link: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub has_requested_feedback: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub notify: Option<bool>
+}
+
+#[derive(Serialize, Debug)]
+pub struct DeleteLevelRequest {
+	#[serde(skip_serializing)]
+	pub level_id: u64,
+	#[serde(rename = "discord_id")]
+	pub discord_user_id: u64
+}
+
 #[derive(Serialize)]
 pub struct UpdateLevelRequestMessageId {
 	pub level_id: u64,
 	// #[serde(rename = "discord_id")]
 	// pub discord_user_id: u64,
-	pub discord_message_id: u64
+	pub discord_message_id: u64,
+	pub discord_channel_id: u64
 }
 
 #[derive(Serialize)]
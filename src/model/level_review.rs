@@ -1,3 +1,5 @@
+use std::fmt::{Display, Formatter};
+
 use serde::Serialize;
 
 #[derive(Serialize)]
@@ -9,10 +11,22 @@ pub struct LevelReview {
 	pub review_contents: String
 }
 
-#[derive(Serialize)]
-pub struct UpdateLevelReviewMessageId {
-	#[serde(rename = "discord_id")]
-	pub discord_user_id: u64,
-	pub level_id: u64,
-	pub discord_message_id: u64
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum LevelReviewOutcome {
+	Posted,
+	Edited,
+	FeedbackNotRequested
+}
+
+impl Display for LevelReviewOutcome {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			LevelReviewOutcome::Posted | LevelReviewOutcome::Edited => {
+				write!(f, "Review submitted")
+			}
+			LevelReviewOutcome::FeedbackNotRequested => {
+				write!(f, "The user has not requested feedback for this level")
+			}
+		}
+	}
 }
@@ -1,7 +1,11 @@
+pub mod audit_event;
+pub mod discord_gateway;
+pub mod discord_user;
 pub mod error;
 pub mod level_request;
 pub mod level_review;
 pub mod moderator;
+pub mod permission;
 pub mod request_score;
 pub mod requestx_api;
 pub mod reviewer;
@@ -1,4 +1,7 @@
-use std::str::FromStr;
+use std::{
+	fmt::{Display, Formatter},
+	str::FromStr
+};
 
 use serde::{Deserialize, Serialize, Serializer};
 
@@ -33,6 +36,24 @@ pub enum SuggestedRating {
 	Mythic
 }
 
+impl Display for SuggestedScore {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			SuggestedScore::NoRate => write!(f, "No Rate"),
+			SuggestedScore::One => write!(f, "One"),
+			SuggestedScore::Two => write!(f, "Two"),
+			SuggestedScore::Three => write!(f, "Three"),
+			SuggestedScore::Four => write!(f, "Four"),
+			SuggestedScore::Five => write!(f, "Five"),
+			SuggestedScore::Six => write!(f, "Six"),
+			SuggestedScore::Seven => write!(f, "Seven"),
+			SuggestedScore::Eight => write!(f, "Eight"),
+			SuggestedScore::Nine => write!(f, "Nine"),
+			SuggestedScore::Ten => write!(f, "Ten")
+		}
+	}
+}
+
 impl FromStr for SuggestedScore {
 	type Err = ();
 
@@ -75,6 +96,18 @@ impl Serialize for SuggestedScore {
 	}
 }
 
+impl Display for SuggestedRating {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			SuggestedRating::Rate => write!(f, "Rate"),
+			SuggestedRating::Feature => write!(f, "Feature"),
+			SuggestedRating::Epic => write!(f, "Epic"),
+			SuggestedRating::Legendary => write!(f, "Legendary"),
+			SuggestedRating::Mythic => write!(f, "Mythic")
+		}
+	}
+}
+
 impl FromStr for SuggestedRating {
 	type Err = ();
 
@@ -0,0 +1,136 @@
+use std::fmt::{Display, Formatter};
+
+use serde::Serialize;
+
+use crate::config::client_config::GuildConfig;
+
+#[derive(PartialEq, PartialOrd, Serialize, Debug, Copy, Clone)]
+pub enum Permission {
+	Requester,
+	Reviewer,
+	Moderator,
+	Admin
+}
+
+impl Permission {
+	pub fn resolve(
+		guild_config: &GuildConfig,
+		discord_user_id: u64,
+		role_ids: &[u64]
+	) -> Option<Permission> {
+		let has_any_role = |configured_role_ids: &[u64]| {
+			configured_role_ids
+				.iter()
+				.any(|role_id| role_ids.contains(role_id))
+		};
+
+		if guild_config
+			.discord_bot_admin_ids
+			.contains(&discord_user_id)
+			|| has_any_role(&guild_config.discord_admin_role_ids)
+		{
+			Some(Permission::Admin)
+		} else if guild_config
+			.discord_moderator_ids
+			.contains(&discord_user_id)
+			|| has_any_role(&guild_config.discord_moderator_role_ids)
+		{
+			Some(Permission::Moderator)
+		} else if role_ids.contains(&guild_config.discord_reviewer_role_id) {
+			Some(Permission::Reviewer)
+		} else if guild_config.discord_requester_role_ids.is_empty()
+			|| has_any_role(&guild_config.discord_requester_role_ids)
+		{
+			Some(Permission::Requester)
+		} else {
+			None
+		}
+	}
+}
+
+impl Display for Permission {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Permission::Requester => {
+				write!(f, "Requester")
+			}
+			Permission::Reviewer => {
+				write!(f, "Reviewer")
+			}
+			Permission::Moderator => {
+				write!(f, "Moderator")
+			}
+			Permission::Admin => {
+				write!(f, "Admin")
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		config::client_config::{GuildConfig, LevelLengthChannelIds},
+		model::permission::Permission
+	};
+
+	fn test_guild_config() -> GuildConfig {
+		GuildConfig {
+			discord_guild_id: 1,
+			discord_request_channel_id: 2,
+			discord_level_length_channel_ids: LevelLengthChannelIds::default(),
+			discord_reviewer_role_id: 3,
+			discord_log_channel_id: 4,
+			discord_bot_admin_ids: vec![10],
+			discord_admin_role_ids: vec![11],
+			discord_moderator_ids: vec![20],
+			discord_moderator_role_ids: vec![21],
+			discord_requester_role_ids: vec![],
+			discord_plain_text_messages: false
+		}
+	}
+
+	#[test]
+	fn admin_should_be_resolved_from_user_or_role() {
+		let guild_config = test_guild_config();
+
+		assert_eq!(
+			Permission::resolve(&guild_config, 10, &[]),
+			Some(Permission::Admin)
+		);
+		assert_eq!(
+			Permission::resolve(&guild_config, 99, &[3, 11]),
+			Some(Permission::Admin)
+		);
+	}
+
+	#[test]
+	fn highest_permission_should_be_resolved() {
+		let guild_config = test_guild_config();
+
+		assert_eq!(
+			Permission::resolve(&guild_config, 20, &[3]),
+			Some(Permission::Moderator)
+		);
+		assert_eq!(
+			Permission::resolve(&guild_config, 99, &[3]),
+			Some(Permission::Reviewer)
+		);
+		assert_eq!(
+			Permission::resolve(&guild_config, 99, &[]),
+			Some(Permission::Requester)
+		);
+	}
+
+	#[test]
+	fn requester_role_should_be_enforced_when_configured() {
+		let mut guild_config = test_guild_config();
+		guild_config.discord_requester_role_ids = vec![30];
+
+		assert_eq!(Permission::resolve(&guild_config, 99, &[]), None);
+		assert_eq!(
+			Permission::resolve(&guild_config, 99, &[30]),
+			Some(Permission::Requester)
+		);
+	}
+}
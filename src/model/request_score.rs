@@ -25,6 +25,7 @@ pub enum LevelLength {
 	Short,
 	Medium,
 	Long,
+	#[serde(alias = "XL")]
 	ExtraLong,
 	Platformer
 }
@@ -66,6 +67,31 @@ impl Display for RequestRating {
 	}
 }
 
+impl Display for LevelLength {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			LevelLength::Tiny => {
+				write!(f, "Tiny")
+			}
+			LevelLength::Short => {
+				write!(f, "Short")
+			}
+			LevelLength::Medium => {
+				write!(f, "Medium")
+			}
+			LevelLength::Long => {
+				write!(f, "Long")
+			}
+			LevelLength::ExtraLong => {
+				write!(f, "XL")
+			}
+			LevelLength::Platformer => {
+				write!(f, "Platformer")
+			}
+		}
+	}
+}
+
 impl FromStr for RequestRating {
 	type Err = ();
 
@@ -0,0 +1,105 @@
+use std::{
+	sync::Mutex,
+	time::{Duration, Instant}
+};
+
+use lazy_static::lazy_static;
+use log::{info, warn};
+
+use crate::config::requestx_api_config::REQUESTX_API_CONFIG;
+
+#[derive(Default)]
+struct CircuitBreakerState {
+	consecutive_failures: u32,
+	opened_at: Option<Instant>
+}
+
+pub struct CircuitBreaker {
+	failure_threshold: u32,
+	reset_timeout: Duration,
+	state: Mutex<CircuitBreakerState>
+}
+
+lazy_static! {
+	pub static ref CIRCUIT_BREAKER: CircuitBreaker = CircuitBreaker::new(
+		REQUESTX_API_CONFIG.policy.circuit_breaker_failure_threshold,
+		Duration::from_secs(REQUESTX_API_CONFIG.policy.circuit_breaker_reset_secs)
+	);
+}
+
+impl CircuitBreaker {
+	pub fn new(failure_threshold: u32, reset_timeout: Duration) -> Self {
+		CircuitBreaker {
+			failure_threshold,
+			reset_timeout,
+			state: Mutex::new(CircuitBreakerState::default())
+		}
+	}
+
+	// Once the reset timeout has passed requests are let through again, a
+	// single failure then reopens the circuit
+	pub fn is_open(&self) -> bool {
+		match self.lock_state().opened_at {
+			Some(opened_at) => opened_at.elapsed() < self.reset_timeout,
+			None => false
+		}
+	}
+
+	pub fn record_success(&self) {
+		let mut state = self.lock_state();
+		if state.opened_at.is_some() {
+			info!("RequestX API has recovered, closing circuit breaker");
+		}
+		*state = CircuitBreakerState::default();
+	}
+
+	pub fn record_failure(&self) {
+		if self.failure_threshold == 0 {
+			return;
+		}
+
+		let mut state = self.lock_state();
+		state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+		if state.consecutive_failures >= self.failure_threshold {
+			warn!(
+				"RequestX API failed {} consecutive times, opening circuit breaker for {:?}",
+				state.consecutive_failures, self.reset_timeout
+			);
+			state.opened_at = Some(Instant::now());
+		}
+	}
+
+	fn lock_state(&self) -> std::sync::MutexGuard<'_, CircuitBreakerState> {
+		self.state
+			.lock()
+			.unwrap_or_else(|poisoned| poisoned.into_inner())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::Duration;
+
+	use crate::model::requestx_api::circuit_breaker::CircuitBreaker;
+
+	#[test]
+	fn circuit_should_open_after_threshold_and_close_on_success() {
+		let circuit_breaker = CircuitBreaker::new(2, Duration::from_secs(30));
+
+		circuit_breaker.record_failure();
+		assert!(!circuit_breaker.is_open());
+		circuit_breaker.record_failure();
+		assert!(circuit_breaker.is_open());
+
+		circuit_breaker.record_success();
+		assert!(!circuit_breaker.is_open());
+	}
+
+	#[test]
+	fn circuit_should_let_requests_through_after_reset_timeout() {
+		let circuit_breaker = CircuitBreaker::new(1, Duration::ZERO);
+
+		circuit_breaker.record_failure();
+		assert!(!circuit_breaker.is_open());
+	}
+}
@@ -0,0 +1,601 @@
+use std::{
+	collections::HashSet,
+	fs, io,
+	path::PathBuf,
+	sync::{Mutex, MutexGuard}
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use log::error;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::model::{
+	error::{
+		level_request_error::LevelRequestError,
+		requestx_error::{RequestXErrorBody, RequestXResponseError}
+	},
+	level_request::{
+		DeleteLevelRequest, GetLevelRequest, GetLevelRequestQueue, GetLevelReview, GetLevelReviews,
+		GetRequesterLevelRequests, LevelRequest, SearchLevelRequests, UpdateLevelRequest,
+		UpdateLevelRequestMessageId, UpdateLevelRequestThreadId
+	},
+	level_review::LevelReview,
+	moderator::Moderator,
+	request_score::LevelLength,
+	requestx_api::{
+		level_request_data::{LevelRequestData, LevelRequestQueueData},
+		level_review_data::LevelReviewData,
+		level_review_error::LevelReviewError,
+		moderator_data::ModeratorError,
+		requestx_api_trait::RequestXApi,
+		reviewer_data::ReviewerError
+	},
+	reviewer::{AddReviewerRequest, RemoveReviewerRequest}
+};
+
+#[derive(Serialize, Deserialize)]
+struct StoredLevelRequest {
+	level_request: LevelRequestData,
+	requested_at: DateTime<Utc>,
+	is_pending: bool
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct InMemoryState {
+	level_requests: Vec<StoredLevelRequest>,
+	level_reviews: Vec<LevelReviewData>,
+	reviewer_discord_ids: HashSet<u64>
+}
+
+// Stands in for the RequestX API in tests and offline development. Levels are
+// not looked up on the Geometry Dash servers, so names and lengths are made up
+#[derive(Default)]
+pub struct InMemoryRequestXApi {
+	state: Mutex<InMemoryState>,
+	persistence_path: Option<PathBuf>
+}
+
+impl InMemoryRequestXApi {
+	// Loads the state saved by a previous run and writes every change back to
+	// the file so the offline mode keeps its requests across restarts
+	pub fn with_persistence(persistence_path: PathBuf) -> Result<Self, io::Error> {
+		let state = match fs::read_to_string(&persistence_path) {
+			Ok(contents) => serde_json::from_str(&contents)?,
+			Err(error) if error.kind() == io::ErrorKind::NotFound => InMemoryState::default(),
+			Err(error) => return Err(error)
+		};
+
+		Ok(InMemoryRequestXApi {
+			state: Mutex::new(state),
+			persistence_path: Some(persistence_path)
+		})
+	}
+
+	fn persist(&self, state: &InMemoryState) {
+		if let Some(persistence_path) = &self.persistence_path {
+			let result = serde_json::to_string_pretty(state)
+				.map_err(io::Error::from)
+				.and_then(|contents| fs::write(persistence_path, contents));
+			if let Err(error) = result {
+				error!(
+					"Unable to save fake backend state to {}: {}",
+					persistence_path.display(),
+					error
+				);
+			}
+		}
+	}
+
+	fn lock_state(&self) -> MutexGuard<'_, InMemoryState> {
+		self.state
+			.lock()
+			.unwrap_or_else(|poisoned| poisoned.into_inner())
+	}
+
+	fn not_found(endpoint: &str) -> RequestXResponseError {
+		RequestXResponseError {
+			status: StatusCode::NOT_FOUND,
+			endpoint: endpoint.to_string(),
+			body: Some(RequestXErrorBody {
+				code: "NOT_FOUND".to_string(),
+				message: "Level request does not exist".to_string()
+			})
+		}
+	}
+
+	fn paginate(
+		level_requests: Vec<LevelRequestData>,
+		page: u64,
+		page_size: u64
+	) -> LevelRequestQueueData {
+		let page_size = page_size.max(1);
+		let total_pages = (level_requests.len() as u64).div_ceil(page_size);
+		LevelRequestQueueData {
+			level_requests: level_requests
+				.into_iter()
+				.skip((page * page_size) as usize)
+				.take(page_size as usize)
+				.collect(),
+			page,
+			total_pages
+		}
+	}
+}
+
+#[async_trait]
+impl RequestXApi for InMemoryRequestXApi {
+	async fn get_level_request(
+		&self,
+		get_level_request: GetLevelRequest
+	) -> Result<Option<LevelRequestData>, LevelRequestError> {
+		Ok(self
+			.lock_state()
+			.level_requests
+			.iter()
+			.find(|stored| stored.level_request.level_id == get_level_request.level_id)
+			.map(|stored| stored.level_request.clone()))
+	}
+
+	async fn get_level_request_queue(
+		&self,
+		get_level_request_queue: GetLevelRequestQueue
+	) -> Result<LevelRequestQueueData, LevelRequestError> {
+		let level_requests = self
+			.lock_state()
+			.level_requests
+			.iter()
+			.filter(|stored| stored.is_pending)
+			.filter(|stored| {
+				get_level_request_queue
+					.request_rating
+					.map_or(true, |request_rating| {
+						stored.level_request.request_score == request_rating
+					})
+			})
+			.filter(|stored| {
+				get_level_request_queue
+					.level_length
+					.map_or(true, |level_length| {
+						stored.level_request.level_length == level_length
+					})
+			})
+			.filter(|stored| {
+				get_level_request_queue.has_requested_feedback.map_or(
+					true,
+					|has_requested_feedback| {
+						stored.level_request.has_requested_feedback == has_requested_feedback
+					}
+				)
+			})
+			.filter(|stored| {
+				get_level_request_queue
+					.max_age_hours
+					.map_or(true, |max_age_hours| {
+						Utc::now() - stored.requested_at <= Duration::hours(max_age_hours as i64)
+					})
+			})
+			.map(|stored| stored.level_request.clone())
+			.collect();
+
+		Ok(Self::paginate(
+			level_requests,
+			get_level_request_queue.page,
+			get_level_request_queue.page_size
+		))
+	}
+
+	async fn search_level_requests(
+		&self,
+		search_level_requests: SearchLevelRequests
+	) -> Result<LevelRequestQueueData, LevelRequestError> {
+		let search = search_level_requests.search.to_lowercase();
+		let level_requests = self
+			.lock_state()
+			.level_requests
+			.iter()
+			.filter(|stored| stored.is_pending)
+			.filter(|stored| {
+				stored
+					.level_request
+					.level_id
+					.to_string()
+					.starts_with(&search)
+					|| stored
+						.level_request
+						.level_name
+						.to_lowercase()
+						.contains(&search)
+			})
+			.map(|stored| stored.level_request.clone())
+			.collect();
+
+		Ok(Self::paginate(
+			level_requests,
+			search_level_requests.page,
+			search_level_requests.page_size
+		))
+	}
+
+	async fn get_requester_level_requests(
+		&self,
+		get_requester_level_requests: GetRequesterLevelRequests
+	) -> Result<LevelRequestQueueData, LevelRequestError> {
+		// Newest first so a requester sees their latest submissions on the
+		// first page
+		let level_requests = self
+			.lock_state()
+			.level_requests
+			.iter()
+			.rev()
+			.filter(|stored| {
+				stored.level_request.discord_id == get_requester_level_requests.discord_user_id
+			})
+			.map(|stored| stored.level_request.clone())
+			.collect();
+
+		Ok(Self::paginate(
+			level_requests,
+			get_requester_level_requests.page,
+			get_requester_level_requests.page_size
+		))
+	}
+
+	async fn get_level_review(
+		&self,
+		get_level_review: GetLevelReview
+	) -> Result<Option<LevelReviewData>, LevelReviewError> {
+		Ok(self
+			.lock_state()
+			.level_reviews
+			.iter()
+			.find(|level_review| {
+				level_review.level_id == get_level_review.level_id
+					&& level_review.discord_user_id == get_level_review.discord_user_id
+			})
+			.cloned())
+	}
+
+	async fn get_level_reviews(
+		&self,
+		get_level_reviews: GetLevelReviews
+	) -> Result<Vec<LevelReviewData>, LevelReviewError> {
+		Ok(self
+			.lock_state()
+			.level_reviews
+			.iter()
+			.filter(|level_review| level_review.level_id == get_level_reviews.level_id)
+			.cloned()
+			.collect())
+	}
+
+	async fn make_requestx_api_level_request(
+		&self,
+		level_request: LevelRequest
+	) -> Result<LevelRequestData, LevelRequestError> {
+		let mut state = self.lock_state();
+		if state
+			.level_requests
+			.iter()
+			.any(|stored| stored.level_request.level_id == level_request.level_id)
+		{
+			return Err(LevelRequestError::LevelRequestExists);
+		}
+
+		let level_request_data = LevelRequestData {
+			level_id: level_request.level_id,
+			discord_id: level_request.discord_user_id,
+			discord_message_id: None,
+			discord_thread_id: None,
+			discord_channel_id: None,
+			level_name: format!("Level {}", level_request.level_id),
+			level_author: "Unknown".to_string(),
+			request_score: level_request.request_score,
+			level_length: LevelLength::Medium,
+			youtube_video_link: level_request.youtube_video_link,
+			has_requested_feedback: level_request.has_requested_feedback,
+			notify: level_request.notify,
+			suggested_score: None,
+			suggested_rating: None
+		};
+		state.level_requests.push(StoredLevelRequest {
+			level_request: level_request_data.clone(),
+			requested_at: Utc::now(),
+			is_pending: true
+		});
+		self.persist(&state);
+		Ok(level_request_data)
+	}
+
+	async fn update_level_request(
+		&self,
+		update_level_request: UpdateLevelRequest
+	) -> Result<LevelRequestData, LevelRequestError> {
+		let mut state = self.lock_state();
+		let result = match state.level_requests.iter_mut().find(|stored| {
+			stored.level_request.level_id == update_level_request.level_id
+				&& stored.level_request.discord_id == update_level_request.discord_user_id
+		}) {
+			Some(stored) => {
+				let level_request = &mut stored.level_request;
+				if let Some(request_score) = update_level_request.request_score {
+					level_request.request_score = request_score;
+				}
+				if let Some(youtube_video_link) = update_level_request.youtube_video_link {
+					level_request.youtube_video_link = youtube_video_link;
+				}
+				if let Some(has_requested_feedback) = update_level_request.has_requested_feedback {
+					level_request.has_requested_feedback = has_requested_feedback;
+				}
+				if let Some(notify) = update_level_request.notify {
+					level_request.notify = notify;
+				}
+				Ok(level_request.clone())
+			}
+			None => Err(Self::not_found("request_level").into())
+		};
+		self.persist(&state);
+		result
+	}
+
+	async fn delete_level_request(
+		&self,
+		delete_level_request: DeleteLevelRequest
+	) -> Result<(), LevelRequestError> {
+		let mut state = self.lock_state();
+		let level_request_count = state.level_requests.len();
+		state.level_requests.retain(|stored| {
+			stored.level_request.level_id != delete_level_request.level_id
+				|| stored.level_request.discord_id != delete_level_request.discord_user_id
+		});
+		if state.level_requests.len() == level_request_count {
+			return Err(Self::not_found("request_level").into());
+		}
+		state
+			.level_reviews
+			.retain(|level_review| level_review.level_id != delete_level_request.level_id);
+		self.persist(&state);
+		Ok(())
+	}
+
+	async fn make_requestx_api_level_review_request(
+		&self,
+		level_review: &LevelReview
+	) -> Result<LevelReviewData, LevelReviewError> {
+		let mut state = self.lock_state();
+		if !state
+			.level_requests
+			.iter()
+			.any(|stored| stored.level_request.level_id == level_review.level_id)
+		{
+			return Err(Self::not_found("review_level").into());
+		}
+
+		let level_review_data = LevelReviewData {
+			level_id: level_review.level_id,
+			discord_user_id: level_review.discord_user_id,
+			discord_message_id: Some(level_review.discord_message_id),
+			review_contents: level_review.review_contents.clone()
+		};
+		state.level_reviews.retain(|existing_level_review| {
+			existing_level_review.level_id != level_review.level_id
+				|| existing_level_review.discord_user_id != level_review.discord_user_id
+		});
+		state.level_reviews.push(level_review_data.clone());
+		self.persist(&state);
+		Ok(level_review_data)
+	}
+
+	async fn make_add_reviewer_request(
+		&self,
+		create_reviewer_request: AddReviewerRequest
+	) -> Result<(), ReviewerError> {
+		let mut state = self.lock_state();
+		state
+			.reviewer_discord_ids
+			.insert(create_reviewer_request.reviewer_discord_id);
+		self.persist(&state);
+		Ok(())
+	}
+
+	async fn make_remove_reviewer_request(
+		&self,
+		remove_reviewer_request: RemoveReviewerRequest
+	) -> Result<(), ReviewerError> {
+		let mut state = self.lock_state();
+		state
+			.reviewer_discord_ids
+			.remove(&remove_reviewer_request.reviewer_discord_id);
+		self.persist(&state);
+		Ok(())
+	}
+
+	async fn make_send_level_request(
+		&self,
+		send_level_request: Moderator
+	) -> Result<LevelRequestData, ModeratorError> {
+		let mut state = self.lock_state();
+		let result = match state
+			.level_requests
+			.iter_mut()
+			.find(|stored| stored.level_request.level_id == send_level_request.level_id)
+		{
+			Some(stored) => {
+				stored.is_pending = false;
+				stored.level_request.suggested_score = Some(send_level_request.suggested_score);
+				stored.level_request.suggested_rating = Some(send_level_request.suggested_rating);
+				Ok(stored.level_request.clone())
+			}
+			None => Err(ModeratorError::LevelRequestDoesNotExist)
+		};
+		self.persist(&state);
+		result
+	}
+
+	async fn update_request_message_id(
+		&self,
+		update_level_request: UpdateLevelRequestMessageId
+	) -> Result<(), LevelRequestError> {
+		let mut state = self.lock_state();
+		let result = match state
+			.level_requests
+			.iter_mut()
+			.find(|stored| stored.level_request.level_id == update_level_request.level_id)
+		{
+			Some(stored) => {
+				stored.level_request.discord_message_id =
+					Some(update_level_request.discord_message_id);
+				stored.level_request.discord_channel_id =
+					Some(update_level_request.discord_channel_id);
+				Ok(())
+			}
+			None => Err(Self::not_found("update_request_message_id").into())
+		};
+		self.persist(&state);
+		result
+	}
+
+	async fn update_request_thread_id(
+		&self,
+		update_level_request: UpdateLevelRequestThreadId
+	) -> Result<(), LevelRequestError> {
+		let mut state = self.lock_state();
+		let result = match state
+			.level_requests
+			.iter_mut()
+			.find(|stored| stored.level_request.level_id == update_level_request.level_id)
+		{
+			Some(stored) => {
+				stored.level_request.discord_thread_id =
+					Some(update_level_request.discord_thread_id);
+				Ok(())
+			}
+			None => Err(Self::not_found("update_request_thread_id").into())
+		};
+		self.persist(&state);
+		result
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{env, fs};
+
+	use tokio_test::assert_ok;
+
+	use crate::model::{
+		error::level_request_error::LevelRequestError,
+		level_request::{GetLevelRequest, GetLevelRequestQueue, LevelRequest, SearchLevelRequests},
+		moderator::{Moderator, SuggestedRating, SuggestedScore},
+		request_score::RequestRating,
+		requestx_api::{
+			in_memory_requestx_api::InMemoryRequestXApi, requestx_api_trait::RequestXApi
+		}
+	};
+
+	fn test_level_request(level_id: u64) -> LevelRequest {
+		LevelRequest {
+			discord_user_id: 164072941645070336,
+			level_id,
+			request_score: RequestRating::Four,
+			youtube_video_link: "https://youtu.be/dQw4w9WgXcQ".to_string(),
+			has_requested_feedback: false,
+			notify: false
+		}
+	}
+
+	#[tokio::test]
+	async fn duplicate_level_request_should_conflict() {
+		let requestx_api = InMemoryRequestXApi::default();
+
+		assert_ok!(
+			requestx_api
+				.make_requestx_api_level_request(test_level_request(97624039))
+				.await
+		);
+		assert_eq!(
+			requestx_api
+				.make_requestx_api_level_request(test_level_request(97624039))
+				.await
+				.unwrap_err(),
+			LevelRequestError::LevelRequestExists
+		);
+	}
+
+	#[tokio::test]
+	async fn sent_levels_should_leave_the_queue() {
+		let requestx_api = InMemoryRequestXApi::default();
+		for level_id in [97624039, 97624040] {
+			requestx_api
+				.make_requestx_api_level_request(test_level_request(level_id))
+				.await
+				.unwrap();
+		}
+		requestx_api
+			.make_send_level_request(Moderator {
+				level_id: 97624039,
+				suggested_score: SuggestedScore::Four,
+				suggested_rating: SuggestedRating::Feature
+			})
+			.await
+			.unwrap();
+
+		let level_request_queue = requestx_api
+			.get_level_request_queue(GetLevelRequestQueue {
+				page: 0,
+				page_size: 10,
+				request_rating: Some(RequestRating::Four),
+				level_length: None,
+				has_requested_feedback: None,
+				max_age_hours: None
+			})
+			.await
+			.unwrap();
+
+		assert_eq!(level_request_queue.total_pages, 1);
+		assert_eq!(level_request_queue.level_requests.len(), 1);
+		assert_eq!(level_request_queue.level_requests[0].level_id, 97624040);
+	}
+
+	#[tokio::test]
+	async fn search_should_match_id_prefix() {
+		let requestx_api = InMemoryRequestXApi::default();
+		requestx_api
+			.make_requestx_api_level_request(test_level_request(97624039))
+			.await
+			.unwrap();
+
+		let search_results = requestx_api
+			.search_level_requests(SearchLevelRequests {
+				page: 0,
+				page_size: 25,
+				search: "9762".to_string()
+			})
+			.await
+			.unwrap();
+
+		assert_eq!(search_results.level_requests.len(), 1);
+	}
+
+	#[tokio::test]
+	async fn persisted_state_should_survive_a_restart() {
+		let persistence_path = env::temp_dir().join("requestx_fake_backend_test.json");
+		let _ = fs::remove_file(&persistence_path);
+
+		InMemoryRequestXApi::with_persistence(persistence_path.clone())
+			.unwrap()
+			.make_requestx_api_level_request(test_level_request(97624039))
+			.await
+			.unwrap();
+		let restarted_requestx_api =
+			InMemoryRequestXApi::with_persistence(persistence_path.clone()).unwrap();
+		let _ = fs::remove_file(&persistence_path);
+
+		assert!(restarted_requestx_api
+			.get_level_request(GetLevelRequest { level_id: 97624039 })
+			.await
+			.unwrap()
+			.is_some());
+	}
+}
@@ -1,18 +1,35 @@
 use serde::{Deserialize, Serialize};
 
-use crate::model::request_score::{LevelLength, RequestRating};
+use crate::model::{
+	moderator::{SuggestedRating, SuggestedScore},
+	request_score::{LevelLength, RequestRating}
+};
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct LevelRequestData {
 	pub level_id: u64,
 	pub discord_id: u64,
 	pub discord_message_id: Option<u64>,
 	pub discord_thread_id: Option<u64>,
+	#[serde(default)]
+	pub discord_channel_id: Option<u64>,
 	pub level_name: String,
 	pub level_author: String,
 	pub request_score: RequestRating,
 	pub level_length: LevelLength,
 	pub youtube_video_link: String,
 	pub has_requested_feedback: bool,
-	pub notify: bool
+	pub notify: bool,
+	// Set once a moderator has decided whether to send the level
+	#[serde(default)]
+	pub suggested_score: Option<SuggestedScore>,
+	#[serde(default)]
+	pub suggested_rating: Option<SuggestedRating>
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LevelRequestQueueData {
+	pub level_requests: Vec<LevelRequestData>,
+	pub page: u64,
+	pub total_pages: u64
 }
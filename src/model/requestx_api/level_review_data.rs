@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct LevelReviewData {
 	pub level_id: u64,
 	#[serde(rename = "reviewer_discord_id")]
@@ -3,12 +3,16 @@ use std::{
 	fmt::{Display, Formatter}
 };
 
+use crate::model::error::{
+	discord_gateway_error::DiscordGatewayError,
+	requestx_error::{RequestXError, RequestXResponseError}
+};
+
 #[derive(Debug, PartialEq)]
 pub enum LevelReviewError {
 	LevelRequestDoesNotExists,
-	RequestError,
-	SerializeError,
-	RequestXApiError
+	RequestX(RequestXError),
+	Discord(DiscordGatewayError)
 }
 
 impl Display for LevelReviewError {
@@ -17,17 +21,30 @@ impl Display for LevelReviewError {
 			LevelReviewError::LevelRequestDoesNotExists => {
 				write!(f, "Level Request does not exist")
 			}
-			LevelReviewError::RequestError => {
-				write!(f, "Unable to make request to server")
-			}
-			LevelReviewError::SerializeError => {
-				write!(f, "Unable to serialized level review")
+			LevelReviewError::RequestX(requestx_error) => {
+				write!(f, "Level review failed: {}", requestx_error)
 			}
-			LevelReviewError::RequestXApiError => {
-				write!(f, "The server failed to make the upload the level review")
+			LevelReviewError::Discord(discord_gateway_error) => {
+				write!(f, "Level review failed: {}", discord_gateway_error)
 			}
 		}
 	}
 }
 
 impl Error for LevelReviewError {}
+
+impl From<RequestXError> for LevelReviewError {
+	fn from(requestx_error: RequestXError) -> Self { LevelReviewError::RequestX(requestx_error) }
+}
+
+impl From<RequestXResponseError> for LevelReviewError {
+	fn from(response_error: RequestXResponseError) -> Self {
+		LevelReviewError::RequestX(response_error.into())
+	}
+}
+
+impl From<DiscordGatewayError> for LevelReviewError {
+	fn from(discord_gateway_error: DiscordGatewayError) -> Self {
+		LevelReviewError::Discord(discord_gateway_error)
+	}
+}
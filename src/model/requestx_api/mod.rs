@@ -1,6 +1,9 @@
+pub mod circuit_breaker;
+pub mod in_memory_requestx_api;
 pub mod level_request_data;
 pub mod level_review_data;
 pub mod level_review_error;
 pub mod moderator_data;
 pub mod requestx_api_client;
+pub mod requestx_api_trait;
 pub mod reviewer_data;
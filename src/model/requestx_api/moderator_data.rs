@@ -3,12 +3,16 @@ use std::{
 	fmt::{Display, Formatter}
 };
 
+use crate::model::error::{
+	discord_gateway_error::DiscordGatewayError,
+	requestx_error::{RequestXError, RequestXResponseError}
+};
+
 #[derive(Debug, PartialEq)]
 pub enum ModeratorError {
 	LevelRequestDoesNotExist,
-	RequestXApiError,
-	SerializeError,
-	RequestError
+	RequestX(RequestXError),
+	Discord(DiscordGatewayError)
 }
 
 impl Display for ModeratorError {
@@ -17,17 +21,30 @@ impl Display for ModeratorError {
 			ModeratorError::LevelRequestDoesNotExist => {
 				write!(f, "Level Request does not exist")
 			}
-			ModeratorError::RequestXApiError => {
-				write!(f, "The server failed to make the send level request")
-			}
-			ModeratorError::SerializeError => {
-				write!(f, "Unable to serialized send level request")
+			ModeratorError::RequestX(requestx_error) => {
+				write!(f, "Send level request failed: {}", requestx_error)
 			}
-			ModeratorError::RequestError => {
-				write!(f, "Unable to make request to server")
+			ModeratorError::Discord(discord_gateway_error) => {
+				write!(f, "Send level request failed: {}", discord_gateway_error)
 			}
 		}
 	}
 }
 
 impl Error for ModeratorError {}
+
+impl From<RequestXError> for ModeratorError {
+	fn from(requestx_error: RequestXError) -> Self { ModeratorError::RequestX(requestx_error) }
+}
+
+impl From<RequestXResponseError> for ModeratorError {
+	fn from(response_error: RequestXResponseError) -> Self {
+		ModeratorError::RequestX(response_error.into())
+	}
+}
+
+impl From<DiscordGatewayError> for ModeratorError {
+	fn from(discord_gateway_error: DiscordGatewayError) -> Self {
+		ModeratorError::Discord(discord_gateway_error)
+	}
+}
@@ -1,8 +1,11 @@
-use log::error;
+use async_trait::async_trait;
+use log::{error, warn};
 use reqwest::{
 	header::{HeaderMap, HeaderValue},
-	Client, StatusCode
+	Client, RequestBuilder, Response, StatusCode
 };
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::time::sleep;
 
 use crate::{
 	config::{
@@ -11,16 +14,25 @@ use crate::{
 		requestx_api_config::{RequestxApiConfig, REQUESTX_API_CONFIG}
 	},
 	model::{
-		error::level_request_error::LevelRequestError,
+		error::{
+			auth_error::AuthError,
+			level_request_error::LevelRequestError,
+			requestx_error::{RequestXError, RequestXResponseError}
+		},
 		level_request::{
-			GetLevelRequest, GetLevelReview, LevelRequest, UpdateLevelRequestMessageId,
-			UpdateLevelRequestThreadId
+			DeleteLevelRequest, GetLevelRequest, GetLevelRequestQueue, GetLevelReview,
+			GetLevelReviews, GetRequesterLevelRequests, LevelRequest, SearchLevelRequests,
+			UpdateLevelRequest, UpdateLevelRequestMessageId, UpdateLevelRequestThreadId
 		},
 		level_review::LevelReview,
 		moderator::Moderator,
 		requestx_api::{
-			level_request_data::LevelRequestData, level_review_data::LevelReviewData,
-			level_review_error::LevelReviewError, moderator_data::ModeratorError,
+			circuit_breaker::CIRCUIT_BREAKER,
+			level_request_data::{LevelRequestData, LevelRequestQueueData},
+			level_review_data::LevelReviewData,
+			level_review_error::LevelReviewError,
+			moderator_data::ModeratorError,
+			requestx_api_trait::RequestXApi,
 			reviewer_data::ReviewerError
 		},
 		reviewer::{AddReviewerRequest, RemoveReviewerRequest}
@@ -28,6 +40,7 @@ use crate::{
 	service::auth_service::JWT
 };
 
+#[derive(Clone)]
 pub struct RequestXApiClient<'a> {
 	requestx_api_config: &'a RequestxApiConfig,
 	web_client: Client
@@ -38,7 +51,7 @@ impl RequestXApiClient<'_> {
 		let mut default_headers = HeaderMap::new();
 		default_headers.insert(CONTENT_TYPE, HeaderValue::from_static(APPLICATION_JSON));
 		RequestXApiClient {
-			requestx_api_config: &*REQUESTX_API_CONFIG,
+			requestx_api_config: &REQUESTX_API_CONFIG,
 			web_client: Client::builder()
 				.default_headers(default_headers)
 				.build()
@@ -46,55 +59,207 @@ impl RequestXApiClient<'_> {
 		}
 	}
 
-	pub async fn get_level_request(
+	// Idempotent requests are retried with exponential backoff on connection
+	// failures and server errors, everything else gets a single attempt. A
+	// rejected token is refreshed and the request replayed once regardless
+	async fn send_request(
 		&self,
-		get_level_request: GetLevelRequest
-	) -> Result<Option<LevelRequestData>, LevelRequestError> {
+		endpoint: &str,
+		request: RequestBuilder,
+		is_idempotent: bool
+	) -> Result<Response, RequestXError> {
+		if CIRCUIT_BREAKER.is_open() {
+			warn!("Circuit breaker is open, skipping request to {}", endpoint);
+			return Err(RequestXError::Unavailable);
+		}
+
+		let policy = &self.requestx_api_config.policy;
+		let request = request.timeout(policy.get_timeout(endpoint));
+		let max_attempts = if is_idempotent {
+			policy.max_retries + 1
+		} else {
+			1
+		};
+		let mut attempt = 1;
+		let mut has_refreshed_token = false;
+		loop {
+			let attempt_request = match request.try_clone() {
+				Some(attempt_request) => attempt_request,
+				None => {
+					error!("Unable to clone request to {}", endpoint);
+					return Err(RequestXError::RequestError);
+				}
+			};
+
+			// Sending the request without a token only produces confusing errors
+			// further down, so authentication failures end the call here
+			let auth_headers = match Self::get_auth_headers().await {
+				Ok(auth_headers) => auth_headers,
+				Err(auth_error) => {
+					error!(
+						"Unable to authenticate request to {}: {}",
+						endpoint, auth_error
+					);
+					return Err(RequestXError::AuthError(auth_error));
+				}
+			};
+			let response = self.execute_request(attempt_request, auth_headers).await;
+			if let Ok(response) = &response {
+				if response.status().eq(&StatusCode::UNAUTHORIZED)
+					|| response.status().eq(&StatusCode::FORBIDDEN)
+				{
+					if has_refreshed_token {
+						error!(
+							"RequestX API rejected the refreshed token for {} with {}",
+							endpoint,
+							response.status()
+						);
+						return Err(RequestXError::AuthError(AuthError::Unauthorized));
+					}
+					warn!(
+						"RequestX API rejected the token for {} with {}, refreshing it",
+						endpoint,
+						response.status()
+					);
+					JWT.invalidate().await;
+					has_refreshed_token = true;
+					continue;
+				}
+			}
+
+			let is_failure = match &response {
+				Ok(response) => response.status().is_server_error(),
+				Err(_) => true
+			};
+			if !is_failure {
+				CIRCUIT_BREAKER.record_success();
+			} else if attempt < max_attempts {
+				let retry_delay = policy.get_retry_delay(attempt);
+				warn!(
+					"Request to {} failed (attempt {}/{}), retrying in {:?}",
+					endpoint, attempt, max_attempts, retry_delay
+				);
+				sleep(retry_delay).await;
+				attempt += 1;
+				continue;
+			} else {
+				CIRCUIT_BREAKER.record_failure();
+			}
+
+			return response.map_err(|error| {
+				error!("Request to {} failed: {}", endpoint, error);
+				RequestXError::RequestError
+			});
+		}
+	}
+
+	// The auth headers are added per attempt so a replayed request picks up
+	// the refreshed token
+	async fn execute_request(
+		&self,
+		request: RequestBuilder,
+		auth_headers: HeaderMap
+	) -> Result<Response, reqwest::Error> {
+		let mut request = request.build()?;
+		request.headers_mut().extend(auth_headers);
+		self.web_client.execute(request).await
+	}
+
+	async fn get_auth_headers() -> Result<HeaderMap, AuthError> {
+		let jwt = JWT.get_jwt().await?;
+		let authorization = match HeaderValue::from_str(format!("Bearer {}", jwt).as_str()) {
+			Ok(authorization) => authorization,
+			Err(error) => {
+				error!("Invalid authorization header: {}", error);
+				return Err(AuthError::InvalidToken);
+			}
+		};
+
 		let mut headers = HeaderMap::new();
-		Self::get_auth_header(&mut headers).await;
-		let response = self
+		headers.insert(
+			&*REQUESTX_API_CONFIG.headers.requestx_discord_app_id,
+			HeaderValue::from(CLIENT_CONFIG.discord_app_id)
+		);
+		headers.insert("authorization", authorization);
+		Ok(headers)
+	}
+
+	async fn parse_response<T: DeserializeOwned>(response: Response) -> Result<T, RequestXError> {
+		let endpoint = response.url().path().to_string();
+		match response.text().await {
+			Ok(response_string) => match serde_json::from_str(&response_string) {
+				Ok(data) => Ok(data),
+				Err(error) => {
+					error!(
+						"Unable to deserialize response from {}: {}. Response body: {}",
+						endpoint, error, response_string
+					);
+					Err(RequestXError::DeserializeError)
+				}
+			},
+			Err(error) => {
+				error!("Unable to read response from {}: {}", endpoint, error);
+				Err(RequestXError::DeserializeError)
+			}
+		}
+	}
+
+	// Only creating a request can conflict with an existing one, a 409 from any
+	// other endpoint is reported as is
+	async fn handle_create_level_request_error(response: Response) -> LevelRequestError {
+		if response.status().eq(&StatusCode::CONFLICT) {
+			LevelRequestError::LevelRequestExists
+		} else {
+			RequestXResponseError::from_response(response).await.into()
+		}
+	}
+
+	async fn handle_moderator_error(response: Response) -> ModeratorError {
+		if response.status().eq(&StatusCode::NOT_FOUND) {
+			ModeratorError::LevelRequestDoesNotExist
+		} else {
+			RequestXResponseError::from_response(response).await.into()
+		}
+	}
+
+	// Listing, searching and the requester's own requests are all filters on
+	// the same endpoint
+	async fn get_level_requests<Q: Serialize>(
+		&self,
+		query: &Q
+	) -> Result<LevelRequestQueueData, LevelRequestError> {
+		let request = self
 			.web_client
 			.get(format!(
-				"{}{}/{}",
-				self.requestx_api_config.base_url,
-				self.requestx_api_config.paths.request_level,
-				get_level_request.level_id
+				"{}{}",
+				self.requestx_api_config.base_url, self.requestx_api_config.paths.request_level
 			))
-			.headers(headers)
-			.send()
-			.await;
+			.query(query);
+		let response = self.send_request("request_level", request, true).await;
 
 		match response {
 			Ok(response) => {
-				if response.status().eq(&StatusCode::NOT_FOUND) {
-					Ok(None)
-				} else if response.status().is_client_error() {
-					Err(RequestXApiClient::handle_level_request_client_error(
-						response.status()
-					))
-				} else if response.status().is_server_error() {
-					Err(LevelRequestError::RequestXApiError)
+				if response.status().is_client_error() || response.status().is_server_error() {
+					Err(RequestXResponseError::from_response(response).await.into())
 				} else {
-					let response_string = response.text().await.unwrap();
-					let level_data: LevelRequestData =
-						serde_json::from_str(&response_string).unwrap();
-					Ok(Some(level_data))
+					Self::parse_response::<LevelRequestQueueData>(response)
+						.await
+						.map_err(LevelRequestError::from)
 				}
 			}
 			Err(error) => {
 				error!("{}", error);
-				Err(LevelRequestError::RequestError)
+				Err(error.into())
 			}
 		}
 	}
 
-	pub async fn get_level_review(
+	async fn fetch_level_review(
 		&self,
-		get_level_review: GetLevelReview
+		get_level_review: GetLevelReview,
+		is_idempotent: bool
 	) -> Result<Option<LevelReviewData>, LevelReviewError> {
-		let mut headers = HeaderMap::new();
-		Self::get_auth_header(&mut headers).await;
-		let response = self
+		let request = self
 			.web_client
 			.get(format!(
 				"{}{}/{}",
@@ -102,242 +267,384 @@ impl RequestXApiClient<'_> {
 				self.requestx_api_config.paths.review_level,
 				get_level_review.level_id
 			))
-			.query(&[("discord_id", get_level_review.discord_user_id)])
-			.headers(headers)
-			.send()
+			.query(&[("discord_id", get_level_review.discord_user_id)]);
+		let response = self
+			.send_request("review_level", request, is_idempotent)
 			.await;
 
 		match response {
 			Ok(response) => {
 				if response.status().eq(&StatusCode::NOT_FOUND) {
 					Ok(None)
-				} else if response.status().is_client_error() {
-					Err(LevelReviewError::RequestXApiError)
-				} else if response.status().is_server_error() {
-					Err(LevelReviewError::RequestXApiError)
+				} else if response.status().is_client_error() || response.status().is_server_error()
+				{
+					Err(RequestXResponseError::from_response(response).await.into())
 				} else {
-					let response_string = response.text().await.unwrap();
-					let level_review_data: LevelReviewData =
-						serde_json::from_str(&response_string).unwrap();
-					Ok(Some(level_review_data))
+					Self::parse_response::<LevelReviewData>(response)
+						.await
+						.map(Some)
+						.map_err(LevelReviewError::from)
 				}
 			}
 			Err(error) => {
 				error!("{}", error);
-				Err(LevelReviewError::RequestError)
+				Err(error.into())
 			}
 		}
 	}
+}
 
-	pub async fn make_requestx_api_level_request(
+#[async_trait]
+impl RequestXApi for RequestXApiClient<'_> {
+	async fn get_level_request(
+		&self,
+		get_level_request: GetLevelRequest
+	) -> Result<Option<LevelRequestData>, LevelRequestError> {
+		let request = self.web_client.get(format!(
+			"{}{}/{}",
+			self.requestx_api_config.base_url,
+			self.requestx_api_config.paths.request_level,
+			get_level_request.level_id
+		));
+		let response = self.send_request("request_level", request, true).await;
+
+		match response {
+			Ok(response) => {
+				if response.status().eq(&StatusCode::NOT_FOUND) {
+					Ok(None)
+				} else if response.status().is_client_error() || response.status().is_server_error()
+				{
+					Err(RequestXResponseError::from_response(response).await.into())
+				} else {
+					Self::parse_response::<LevelRequestData>(response)
+						.await
+						.map(Some)
+						.map_err(LevelRequestError::from)
+				}
+			}
+			Err(error) => {
+				error!("{}", error);
+				Err(error.into())
+			}
+		}
+	}
+
+	async fn get_level_request_queue(
+		&self,
+		get_level_request_queue: GetLevelRequestQueue
+	) -> Result<LevelRequestQueueData, LevelRequestError> {
+		self.get_level_requests(&get_level_request_queue).await
+	}
+
+	async fn search_level_requests(
+		&self,
+		search_level_requests: SearchLevelRequests
+	) -> Result<LevelRequestQueueData, LevelRequestError> {
+		self.get_level_requests(&search_level_requests).await
+	}
+
+	async fn get_requester_level_requests(
+		&self,
+		get_requester_level_requests: GetRequesterLevelRequests
+	) -> Result<LevelRequestQueueData, LevelRequestError> {
+		self.get_level_requests(&get_requester_level_requests).await
+	}
+
+	async fn get_level_review(
+		&self,
+		get_level_review: GetLevelReview
+	) -> Result<Option<LevelReviewData>, LevelReviewError> {
+		self.fetch_level_review(get_level_review, true).await
+	}
+
+	async fn get_level_review_once(
+		&self,
+		get_level_review: GetLevelReview
+	) -> Result<Option<LevelReviewData>, LevelReviewError> {
+		self.fetch_level_review(get_level_review, false).await
+	}
+
+	async fn get_level_reviews(
+		&self,
+		get_level_reviews: GetLevelReviews
+	) -> Result<Vec<LevelReviewData>, LevelReviewError> {
+		let request = self
+			.web_client
+			.get(format!(
+				"{}{}",
+				self.requestx_api_config.base_url, self.requestx_api_config.paths.review_level
+			))
+			.query(&get_level_reviews);
+		let response = self.send_request("review_level", request, true).await;
+
+		match response {
+			Ok(response) => {
+				if response.status().is_client_error() || response.status().is_server_error() {
+					Err(RequestXResponseError::from_response(response).await.into())
+				} else {
+					Self::parse_response::<Vec<LevelReviewData>>(response)
+						.await
+						.map_err(LevelReviewError::from)
+				}
+			}
+			Err(error) => {
+				error!("{}", error);
+				Err(error.into())
+			}
+		}
+	}
+
+	async fn make_requestx_api_level_request(
 		&self,
 		level_request: LevelRequest
 	) -> Result<LevelRequestData, LevelRequestError> {
 		match serde_json::to_string(&level_request) {
 			Ok(serialized_request) => {
-				let mut headers = HeaderMap::new();
-				Self::get_auth_header(&mut headers).await;
-				let response = self
+				let request = self
 					.web_client
 					.post(format!(
 						"{}{}",
 						self.requestx_api_config.base_url,
 						self.requestx_api_config.paths.request_level
 					))
-					.body(serialized_request)
-					.headers(headers)
-					.send()
-					.await;
+					.body(serialized_request);
+				let response = self.send_request("request_level", request, false).await;
 
 				match response {
 					Ok(response) => {
-						if response.status().is_client_error() {
-							Err(RequestXApiClient::handle_level_request_client_error(
-								response.status()
-							))
-						} else if response.status().is_server_error() {
-							Err(LevelRequestError::RequestXApiError)
+						if response.status().is_client_error()
+							|| response.status().is_server_error()
+						{
+							Err(
+								RequestXApiClient::handle_create_level_request_error(response)
+									.await
+							)
 						} else {
-							let response_string = response.text().await.unwrap();
-							let level_data: LevelRequestData =
-								serde_json::from_str(&response_string).unwrap();
-							Ok(level_data)
+							Self::parse_response::<LevelRequestData>(response)
+								.await
+								.map_err(LevelRequestError::from)
 						}
 					}
 					Err(error) => {
 						error!("{}", error);
-						Err(LevelRequestError::RequestError)
+						Err(error.into())
 					}
 				}
 			}
 			Err(err) => {
 				error!("Error serializing make level request: {}", err);
-				Err(LevelRequestError::SerializeError)
+				Err(RequestXError::SerializeError.into())
+			}
+		}
+	}
+
+	async fn update_level_request(
+		&self,
+		update_level_request: UpdateLevelRequest
+	) -> Result<LevelRequestData, LevelRequestError> {
+		match serde_json::to_string(&update_level_request) {
+			Ok(serialized_request) => {
+				let request = self
+					.web_client
+					.patch(format!(
+						"{}{}/{}",
+						self.requestx_api_config.base_url,
+						self.requestx_api_config.paths.request_level,
+						update_level_request.level_id
+					))
+					.body(serialized_request);
+				let response = self.send_request("request_level", request, true).await;
+
+				match response {
+					Ok(response) => {
+						if response.status().is_client_error()
+							|| response.status().is_server_error()
+						{
+							Err(RequestXResponseError::from_response(response).await.into())
+						} else {
+							Self::parse_response::<LevelRequestData>(response)
+								.await
+								.map_err(LevelRequestError::from)
+						}
+					}
+					Err(error) => {
+						error!("{}", error);
+						Err(error.into())
+					}
+				}
+			}
+			Err(err) => {
+				error!("Failed to serialize update level request: {}", err);
+				Err(RequestXError::SerializeError.into())
+			}
+		}
+	}
+
+	async fn delete_level_request(
+		&self,
+		delete_level_request: DeleteLevelRequest
+	) -> Result<(), LevelRequestError> {
+		let request = self
+			.web_client
+			.delete(format!(
+				"{}{}/{}",
+				self.requestx_api_config.base_url,
+				self.requestx_api_config.paths.request_level,
+				delete_level_request.level_id
+			))
+			.query(&delete_level_request);
+		let response = self.send_request("request_level", request, false).await;
+
+		match response {
+			Ok(response) => {
+				if response.status().is_client_error() || response.status().is_server_error() {
+					Err(RequestXResponseError::from_response(response).await.into())
+				} else {
+					Ok(())
+				}
+			}
+			Err(error) => {
+				error!("{}", error);
+				Err(error.into())
 			}
 		}
 	}
 
-	pub async fn make_requestx_api_level_review_request(
+	async fn make_requestx_api_level_review_request(
 		&self,
 		level_review: &LevelReview
 	) -> Result<LevelReviewData, LevelReviewError> {
 		match serde_json::to_string(&level_review) {
 			Ok(serialized_request) => {
-				let mut headers = HeaderMap::new();
-				Self::get_auth_header(&mut headers).await;
-				let response = self
+				let request = self
 					.web_client
 					.post(format!(
 						"{}{}",
 						self.requestx_api_config.base_url,
 						self.requestx_api_config.paths.review_level
 					))
-					.body(serialized_request)
-					.headers(headers)
-					.send()
-					.await;
+					.body(serialized_request);
+				let response = self.send_request("review_level", request, false).await;
 
 				match response {
 					Ok(response) => {
-						if response.status().is_client_error() {
-							Err(LevelReviewError::RequestXApiError)
-						} else if response.status().is_server_error() {
-							Err(LevelReviewError::RequestXApiError)
+						if response.status().is_client_error()
+							|| response.status().is_server_error()
+						{
+							Err(RequestXResponseError::from_response(response).await.into())
 						} else {
-							let response_string = response.text().await.unwrap();
-							let level_review_data: LevelReviewData =
-								serde_json::from_str(&response_string).unwrap();
-							Ok(level_review_data)
+							Self::parse_response::<LevelReviewData>(response)
+								.await
+								.map_err(LevelReviewError::from)
 						}
 					}
 					Err(error) => {
 						error!("{}", error);
-						Err(LevelReviewError::RequestError)
+						Err(error.into())
 					}
 				}
 			}
 			Err(err) => {
 				error!("Unable to serialize review level request: {}", err);
-				Err(LevelReviewError::SerializeError)
+				Err(RequestXError::SerializeError.into())
 			}
 		}
 	}
 
-	pub async fn make_add_reviewer_request(
+	async fn make_add_reviewer_request(
 		&self,
 		create_reviewer_request: AddReviewerRequest
 	) -> Result<(), ReviewerError> {
 		match serde_json::to_string(&create_reviewer_request) {
 			Ok(serialized_request) => {
-				let mut headers = HeaderMap::new();
-				Self::get_auth_header(&mut headers).await;
-				let response = self
+				let request = self
 					.web_client
 					.post(format!(
 						"{}{}",
 						self.requestx_api_config.base_url, self.requestx_api_config.paths.reviewer
 					))
-					.body(serialized_request)
-					.headers(headers)
-					.send()
-					.await;
+					.body(serialized_request);
+				let response = self.send_request("reviewer", request, false).await;
 
 				match response {
 					Ok(response) => {
-						if response.status().is_client_error() {
-							Err(ReviewerError::RequestXApiError)
-						} else if response.status().is_server_error() {
-							Err(ReviewerError::RequestXApiError)
+						if response.status().is_client_error()
+							|| response.status().is_server_error()
+						{
+							Err(RequestXResponseError::from_response(response).await.into())
 						} else {
 							Ok(())
 						}
 					}
 					Err(error) => {
 						error!("{}", error);
-						Err(ReviewerError::RequestError)
+						Err(error.into())
 					}
 				}
 			}
 			Err(err) => {
 				error!("Unable to make add reviewer request: {}", err);
-				Err(ReviewerError::SerializeError)
+				Err(RequestXError::SerializeError.into())
 			}
 		}
 	}
 
-	pub async fn make_remove_reviewer_request(
+	async fn make_remove_reviewer_request(
 		&self,
 		remove_reviewer_request: RemoveReviewerRequest
 	) -> Result<(), ReviewerError> {
-		let mut headers = HeaderMap::new();
-		Self::get_auth_header(&mut headers).await;
-		let response = self
-			.web_client
-			.delete(format!(
-				"{}{}/{}",
-				self.requestx_api_config.base_url,
-				self.requestx_api_config.paths.reviewer,
-				remove_reviewer_request.reviewer_discord_id
-			))
-			.headers(headers)
-			.send()
-			.await;
+		let request = self.web_client.delete(format!(
+			"{}{}/{}",
+			self.requestx_api_config.base_url,
+			self.requestx_api_config.paths.reviewer,
+			remove_reviewer_request.reviewer_discord_id
+		));
+		let response = self.send_request("reviewer", request, false).await;
 
 		match response {
 			Ok(response) => {
-				if response.status().is_client_error() {
-					Err(ReviewerError::RequestXApiError)
-				} else if response.status().is_server_error() {
-					Err(ReviewerError::RequestXApiError)
+				if response.status().is_client_error() || response.status().is_server_error() {
+					Err(RequestXResponseError::from_response(response).await.into())
 				} else {
 					Ok(())
 				}
 			}
 			Err(error) => {
 				error!("{}", error);
-				Err(ReviewerError::RequestError)
+				Err(error.into())
 			}
 		}
 	}
 
-	pub async fn make_send_level_request(
+	async fn make_send_level_request(
 		&self,
 		send_level_request: Moderator
 	) -> Result<LevelRequestData, ModeratorError> {
 		match serde_json::to_string(&send_level_request) {
 			Ok(serialized_request) => {
-				let mut headers = HeaderMap::new();
-				Self::get_auth_header(&mut headers).await;
-				let response = self
+				let request = self
 					.web_client
 					.post(format!(
 						"{}{}",
 						self.requestx_api_config.base_url,
 						self.requestx_api_config.paths.send_level,
 					))
-					.body(serialized_request)
-					.headers(headers)
-					.send()
-					.await;
+					.body(serialized_request);
+				let response = self.send_request("send_level", request, false).await;
 
 				match response {
 					Ok(resp) => {
-						if resp.status().is_client_error() {
-							Err(RequestXApiClient::handle_moderator_client_error(
-								resp.status()
-							))
-						} else if resp.status().is_server_error() {
-							Err(ModeratorError::RequestXApiError)
+						if resp.status().is_client_error() || resp.status().is_server_error() {
+							Err(RequestXApiClient::handle_moderator_error(resp).await)
 						} else {
-							let response_string = resp.text().await.unwrap();
-							let level_request_data: LevelRequestData =
-								serde_json::from_str(&response_string).unwrap();
-							Ok(level_request_data)
+							Self::parse_response::<LevelRequestData>(resp)
+								.await
+								.map_err(ModeratorError::from)
 						}
 					}
 					Err(send_level_error) => {
 						error!("Unable to send level: {}", send_level_error);
-						Err(ModeratorError::RequestError)
+						Err(send_level_error.into())
 					}
 				}
 			}
@@ -346,91 +653,83 @@ impl RequestXApiClient<'_> {
 					"Unable to serialize send level request: {}",
 					serialize_error
 				);
-				Err(ModeratorError::SerializeError)
+				Err(RequestXError::SerializeError.into())
 			}
 		}
 	}
 
-	pub async fn update_request_message_id(
+	async fn update_request_message_id(
 		&self,
 		update_level_request: UpdateLevelRequestMessageId
 	) -> Result<(), LevelRequestError> {
 		match serde_json::to_string(&update_level_request) {
 			Ok(serialized_request) => {
-				let mut headers = HeaderMap::new();
-				Self::get_auth_header(&mut headers).await;
-				let response = self
+				let request = self
 					.web_client
 					.patch(format!(
 						"{}{}",
 						self.requestx_api_config.base_url,
 						self.requestx_api_config.paths.update_request_message_id
 					))
-					.body(serialized_request)
-					.headers(headers)
-					.send()
+					.body(serialized_request);
+				let response = self
+					.send_request("update_request_message_id", request, true)
 					.await;
 
 				match response {
 					Ok(response) => {
-						if response.status().is_client_error() {
-							Err(RequestXApiClient::handle_level_request_client_error(
-								response.status()
-							))
-						} else if response.status().is_server_error() {
-							Err(LevelRequestError::RequestXApiError)
+						if response.status().is_client_error()
+							|| response.status().is_server_error()
+						{
+							Err(RequestXResponseError::from_response(response).await.into())
 						} else {
 							Ok(())
 						}
 					}
 					Err(error) => {
 						error!("{}", error);
-						Err(LevelRequestError::RequestError)
+						Err(error.into())
 					}
 				}
 			}
 			Err(err) => {
 				error!("Failed to serialize update message ID request: {}", err);
-				Err(LevelRequestError::SerializeError)
+				Err(RequestXError::SerializeError.into())
 			}
 		}
 	}
 
-	pub async fn update_request_thread_id(
+	async fn update_request_thread_id(
 		&self,
 		update_level_request: UpdateLevelRequestThreadId
 	) -> Result<(), LevelRequestError> {
 		match serde_json::to_string(&update_level_request) {
 			Ok(serialized_request) => {
-				let mut headers = HeaderMap::new();
-				Self::get_auth_header(&mut headers).await;
-				let response = self
+				let request = self
 					.web_client
 					.patch(format!(
 						"{}{}",
 						self.requestx_api_config.base_url,
 						self.requestx_api_config.paths.update_request_thread_id
 					))
-					.body(serialized_request)
-					.headers(headers)
-					.send()
+					.body(serialized_request);
+				let response = self
+					.send_request("update_request_thread_id", request, true)
 					.await;
 
 				match response {
 					Ok(response) => {
-						if response.status().is_client_error() {
-							Err(RequestXApiClient::handle_level_request_client_error(
-								response.status()
-							))
-						} else if response.status().is_server_error() {
-							Err(LevelRequestError::RequestXApiError)
+						if response.status().is_client_error()
+							|| response.status().is_server_error()
+						{
+							Err(RequestXResponseError::from_response(response).await.into())
 						} else {
 							Ok(())
 						}
 					}
 					Err(error) => {
 						error!("{}", error);
-						Err(LevelRequestError::RequestError)
+						Err(error.into())
 					}
 				}
 			}
@@ -439,66 +738,40 @@ impl RequestXApiClient<'_> {
 					"Unable to serialize update level request thread ID: {}",
 					err
 				);
-				Err(LevelRequestError::SerializeError)
+				Err(RequestXError::SerializeError.into())
 			}
 		}
 	}
-
-	async fn get_auth_header(headers: &mut HeaderMap) {
-		match &JWT.get_jwt().await {
-			Ok(jwt) => {
-				headers.insert(
-					&*REQUESTX_API_CONFIG.headers.requestx_discord_app_id,
-					HeaderValue::from(CLIENT_CONFIG.discord_app_id)
-				);
-				headers.insert(
-					"authorization",
-					HeaderValue::from_str(format!("Bearer {}", jwt).as_str()).unwrap()
-				);
-			}
-			Err(error) => {
-				error!("Error getting auth headers: {}", error)
-			}
-		}
-	}
-
-	fn handle_level_request_client_error(response_status: StatusCode) -> LevelRequestError {
-		if response_status.eq(&StatusCode::CONFLICT) {
-			LevelRequestError::LevelRequestExists
-		} else {
-			LevelRequestError::RequestXApiError
-		}
-	}
-
-	fn handle_moderator_client_error(response_status: StatusCode) -> ModeratorError {
-		if response_status.eq(&StatusCode::NOT_FOUND) {
-			ModeratorError::LevelRequestDoesNotExist
-		} else {
-			ModeratorError::RequestXApiError
-		}
-	}
 }
 
 #[cfg(test)]
 mod tests {
-	use httpmock::MockServer;
+	use httpmock::Method::{DELETE, GET, POST};
+	use serde_json::json;
 	use tokio_test::{assert_err, assert_ok};
 
 	use crate::{
 		config::requestx_api_config::REQUESTX_API_CONFIG,
 		model::{
-			level_request::LevelRequest, request_score::RequestRating,
-			requestx_api::requestx_api_client::RequestXApiClient
-		}
+			error::{
+				auth_error::AuthError,
+				level_request_error::LevelRequestError,
+				requestx_error::{RequestXError, RequestXErrorBody, RequestXResponseError}
+			},
+			level_request::{
+				GetLevelRequest, GetLevelRequestQueue, LevelRequest, SearchLevelRequests
+			},
+			moderator::{Moderator, SuggestedRating, SuggestedScore},
+			request_score::{LevelLength, RequestRating},
+			requestx_api::{
+				moderator_data::ModeratorError, requestx_api_client::RequestXApiClient,
+				requestx_api_trait::RequestXApi, reviewer_data::ReviewerError
+			},
+			reviewer::RemoveReviewerRequest
+		},
+		test_harness::init_mock_server
 	};
 
-	async fn init_mock_server() -> MockServer {
-		let url = url::Url::parse(&*REQUESTX_API_CONFIG.base_url).unwrap();
-		let host = url.host_str().unwrap();
-		let port = url.port().unwrap();
-		MockServer::connect_async(&*format!("{}:{}", host, port)).await
-	}
-
 	#[tokio::test]
 	async fn level_request_should_succeed() {
 		let server = init_mock_server().await;
@@ -507,12 +780,25 @@ mod tests {
 			level_id: 97624039,
 			request_score: RequestRating::One,
 			youtube_video_link: "Some".to_string(),
-			has_requested_feedback: false
+			has_requested_feedback: false,
+			notify: false
 		};
-		let mock = server.mock(|when, then| {
+		let _mock = server.mock(|when, then| {
 			when.path(&*REQUESTX_API_CONFIG.paths.request_level)
 				.body(serde_json::to_string(&test_request).unwrap());
-			then.status(201);
+			then.status(201).json_body(json!({
+				"level_id": 97624039,
+				"discord_id": 164072941645070336u64,
+				"discord_message_id": null,
+				"discord_thread_id": null,
+				"level_name": "Some Level",
+				"level_author": "Some Author",
+				"request_score": "One",
+				"level_length": "Short",
+				"youtube_video_link": "Some",
+				"has_requested_feedback": false,
+				"notify": false
+			}));
 		});
 
 		let test_client = RequestXApiClient::new();
@@ -532,9 +818,10 @@ mod tests {
 			level_id: 97624039,
 			request_score: RequestRating::One,
 			youtube_video_link: "SOME".to_string(),
-			has_requested_feedback: false
+			has_requested_feedback: false,
+			notify: false
 		};
-		let mock = server.mock(|when, then| {
+		let _mock = server.mock(|when, then| {
 			when.path(&*REQUESTX_API_CONFIG.paths.request_level)
 				.body(serde_json::to_string(&test_request).unwrap());
 			then.status(500);
@@ -548,4 +835,165 @@ mod tests {
 				.await
 		);
 	}
+
+	#[tokio::test]
+	async fn level_request_queue_should_send_filters_as_query() {
+		let server = init_mock_server().await;
+		let test_request = GetLevelRequestQueue {
+			page: 1,
+			page_size: 10,
+			request_rating: Some(RequestRating::Ten),
+			level_length: Some(LevelLength::Platformer),
+			has_requested_feedback: None,
+			max_age_hours: Some(24)
+		};
+		let _mock = server.mock(|when, then| {
+			when.path(&*REQUESTX_API_CONFIG.paths.request_level)
+				.query_param("page", "1")
+				.query_param("page_size", "10")
+				.query_param("request_rating", "Ten")
+				.query_param("level_length", "Platformer")
+				.query_param("max_age_hours", "24");
+			then.status(200).json_body(json!({
+				"level_requests": [],
+				"page": 1,
+				"total_pages": 1
+			}));
+		});
+
+		let test_client = RequestXApiClient::new();
+
+		assert_ok!(test_client.get_level_request_queue(test_request).await);
+	}
+
+	#[tokio::test]
+	async fn send_level_should_surface_server_error_body() {
+		let server = init_mock_server().await;
+		let test_request = Moderator {
+			level_id: 12345678,
+			suggested_score: SuggestedScore::Four,
+			suggested_rating: SuggestedRating::Feature
+		};
+		let _mock = server.mock(|when, then| {
+			when.method(POST)
+				.path(&*REQUESTX_API_CONFIG.paths.send_level);
+			then.status(422).json_body(json!({
+				"code": "LEVEL_ALREADY_RATED",
+				"message": "level is already rated"
+			}));
+		});
+
+		let test_client = RequestXApiClient::new();
+		let error = test_client
+			.make_send_level_request(test_request)
+			.await
+			.unwrap_err();
+
+		assert_eq!(
+			error,
+			ModeratorError::RequestX(RequestXError::ResponseError(RequestXResponseError {
+				status: reqwest::StatusCode::UNPROCESSABLE_ENTITY,
+				endpoint: REQUESTX_API_CONFIG.paths.send_level.clone(),
+				body: Some(RequestXErrorBody {
+					code: "LEVEL_ALREADY_RATED".to_string(),
+					message: "level is already rated".to_string()
+				})
+			}))
+		);
+	}
+
+	#[tokio::test]
+	async fn malformed_level_request_should_return_deserialize_error() {
+		let server = init_mock_server().await;
+		let test_request = GetLevelRequest { level_id: 11111111 };
+		let _mock = server.mock(|when, then| {
+			when.method(GET).path(format!(
+				"{}/{}",
+				REQUESTX_API_CONFIG.paths.request_level, test_request.level_id
+			));
+			then.status(200)
+				.json_body(json!({ "level_id": "not a number" }));
+		});
+
+		let test_client = RequestXApiClient::new();
+
+		assert_eq!(
+			test_client
+				.get_level_request(test_request)
+				.await
+				.unwrap_err(),
+			LevelRequestError::RequestX(RequestXError::DeserializeError)
+		);
+	}
+
+	#[tokio::test]
+	async fn idempotent_request_should_be_retried_on_server_error() {
+		let server = init_mock_server().await;
+		let test_request = SearchLevelRequests {
+			page: 0,
+			page_size: 25,
+			search: "retry".to_string()
+		};
+		let mock = server.mock(|when, then| {
+			when.method(GET)
+				.path(&*REQUESTX_API_CONFIG.paths.request_level)
+				.query_param("search", "retry");
+			then.status(503);
+		});
+
+		let test_client = RequestXApiClient::new();
+
+		assert_err!(test_client.search_level_requests(test_request).await);
+		mock.assert_hits_async(REQUESTX_API_CONFIG.policy.max_retries as usize + 1)
+			.await;
+	}
+
+	#[tokio::test]
+	async fn rejected_token_should_be_refreshed_and_replayed_once() {
+		let server = init_mock_server().await;
+		let test_request = RemoveReviewerRequest {
+			reviewer_discord_id: 424242
+		};
+		let mock = server.mock(|when, then| {
+			when.method(DELETE).path(format!(
+				"{}/{}",
+				REQUESTX_API_CONFIG.paths.reviewer, test_request.reviewer_discord_id
+			));
+			then.status(401);
+		});
+
+		let test_client = RequestXApiClient::new();
+
+		assert_eq!(
+			test_client
+				.make_remove_reviewer_request(test_request)
+				.await
+				.unwrap_err(),
+			ReviewerError::RequestX(RequestXError::AuthError(AuthError::Unauthorized))
+		);
+		mock.assert_hits_async(2).await;
+	}
+
+	#[tokio::test]
+	async fn conflict_outside_level_request_creation_should_not_mean_request_exists() {
+		let server = init_mock_server().await;
+		let test_request = GetLevelRequest { level_id: 22222222 };
+		let _mock = server.mock(|when, then| {
+			when.method(GET).path(format!(
+				"{}/{}",
+				REQUESTX_API_CONFIG.paths.request_level, test_request.level_id
+			));
+			then.status(409);
+		});
+
+		let test_client = RequestXApiClient::new();
+
+		assert!(matches!(
+			test_client
+				.get_level_request(test_request)
+				.await
+				.unwrap_err(),
+			LevelRequestError::RequestX(RequestXError::ResponseError(_))
+		));
+	}
 }
@@ -0,0 +1,107 @@
+use async_trait::async_trait;
+
+use crate::model::{
+	error::level_request_error::LevelRequestError,
+	level_request::{
+		DeleteLevelRequest, GetLevelRequest, GetLevelRequestQueue, GetLevelReview, GetLevelReviews,
+		GetRequesterLevelRequests, LevelRequest, SearchLevelRequests, UpdateLevelRequest,
+		UpdateLevelRequestMessageId, UpdateLevelRequestThreadId
+	},
+	level_review::LevelReview,
+	moderator::Moderator,
+	requestx_api::{
+		level_request_data::{LevelRequestData, LevelRequestQueueData},
+		level_review_data::LevelReviewData,
+		level_review_error::LevelReviewError,
+		moderator_data::ModeratorError,
+		reviewer_data::ReviewerError
+	},
+	reviewer::{AddReviewerRequest, RemoveReviewerRequest}
+};
+
+#[async_trait]
+pub trait RequestXApi: Send + Sync {
+	async fn get_level_request(
+		&self,
+		get_level_request: GetLevelRequest
+	) -> Result<Option<LevelRequestData>, LevelRequestError>;
+
+	async fn get_level_request_queue(
+		&self,
+		get_level_request_queue: GetLevelRequestQueue
+	) -> Result<LevelRequestQueueData, LevelRequestError>;
+
+	async fn search_level_requests(
+		&self,
+		search_level_requests: SearchLevelRequests
+	) -> Result<LevelRequestQueueData, LevelRequestError>;
+
+	async fn get_requester_level_requests(
+		&self,
+		get_requester_level_requests: GetRequesterLevelRequests
+	) -> Result<LevelRequestQueueData, LevelRequestError>;
+
+	async fn get_level_review(
+		&self,
+		get_level_review: GetLevelReview
+	) -> Result<Option<LevelReviewData>, LevelReviewError>;
+
+	// Skips the retry policy, for lookups that have to finish within Discord's
+	// interaction response window
+	async fn get_level_review_once(
+		&self,
+		get_level_review: GetLevelReview
+	) -> Result<Option<LevelReviewData>, LevelReviewError> {
+		self.get_level_review(get_level_review).await
+	}
+
+	async fn get_level_reviews(
+		&self,
+		get_level_reviews: GetLevelReviews
+	) -> Result<Vec<LevelReviewData>, LevelReviewError>;
+
+	async fn make_requestx_api_level_request(
+		&self,
+		level_request: LevelRequest
+	) -> Result<LevelRequestData, LevelRequestError>;
+
+	async fn update_level_request(
+		&self,
+		update_level_request: UpdateLevelRequest
+	) -> Result<LevelRequestData, LevelRequestError>;
+
+	async fn delete_level_request(
+		&self,
+		delete_level_request: DeleteLevelRequest
+	) -> Result<(), LevelRequestError>;
+
+	async fn make_requestx_api_level_review_request(
+		&self,
+		level_review: &LevelReview
+	) -> Result<LevelReviewData, LevelReviewError>;
+
+	async fn make_add_reviewer_request(
+		&self,
+		create_reviewer_request: AddReviewerRequest
+	) -> Result<(), ReviewerError>;
+
+	async fn make_remove_reviewer_request(
+		&self,
+		remove_reviewer_request: RemoveReviewerRequest
+	) -> Result<(), ReviewerError>;
+
+	async fn make_send_level_request(
+		&self,
+		send_level_request: Moderator
+	) -> Result<LevelRequestData, ModeratorError>;
+
+	async fn update_request_message_id(
+		&self,
+		update_level_request: UpdateLevelRequestMessageId
+	) -> Result<(), LevelRequestError>;
+
+	async fn update_request_thread_id(
+		&self,
+		update_level_request: UpdateLevelRequestThreadId
+	) -> Result<(), LevelRequestError>;
+}
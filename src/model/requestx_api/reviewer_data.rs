@@ -1,32 +1,41 @@
 use std::fmt::{Display, Formatter};
 
-use serde::{Deserialize, Serialize};
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct ReviewerData {
-	pub reviewer_discord_id: u64,
-	pub is_active: bool
-}
+use crate::model::error::{
+	discord_gateway_error::DiscordGatewayError,
+	requestx_error::{RequestXError, RequestXResponseError}
+};
 
 #[derive(Debug, PartialEq)]
 pub enum ReviewerError {
-	RequestError,
-	SerializeError,
-	RequestXApiError
+	RequestX(RequestXError),
+	Discord(DiscordGatewayError)
 }
 
 impl Display for ReviewerError {
 	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
 		match self {
-			ReviewerError::RequestError => {
-				write!(f, "Unable to make request to server")
-			}
-			ReviewerError::SerializeError => {
-				write!(f, "Unable to serialized reviewer")
+			ReviewerError::RequestX(requestx_error) => {
+				write!(f, "Reviewer request failed: {}", requestx_error)
 			}
-			ReviewerError::RequestXApiError => {
-				write!(f, "The server failed to handle the request")
+			ReviewerError::Discord(discord_gateway_error) => {
+				write!(f, "Reviewer request failed: {}", discord_gateway_error)
 			}
 		}
 	}
 }
+
+impl From<RequestXError> for ReviewerError {
+	fn from(requestx_error: RequestXError) -> Self { ReviewerError::RequestX(requestx_error) }
+}
+
+impl From<RequestXResponseError> for ReviewerError {
+	fn from(response_error: RequestXResponseError) -> Self {
+		ReviewerError::RequestX(response_error.into())
+	}
+}
+
+impl From<DiscordGatewayError> for ReviewerError {
+	fn from(discord_gateway_error: DiscordGatewayError) -> Self {
+		ReviewerError::Discord(discord_gateway_error)
+	}
+}
@@ -1,13 +1,21 @@
 use async_trait::async_trait;
-use log::{debug, info};
+use log::{debug, error, info, warn};
 use serenity::{
 	all::{GuildId, Interaction, Ready},
 	prelude::{Context, EventHandler}
 };
 
 use crate::{
-	commands::{request_level, review, reviewer, send_level},
-	config::client_config::CLIENT_CONFIG
+	commands::{
+		autocomplete, queue, request_actions, request_level, request_manage, request_status,
+		review, reviewer, send_level
+	},
+	config::client_config::{GuildConfig, CLIENT_CONFIG},
+	service::services::Services,
+	util::{
+		discord::invoke_ephermal,
+		permission::{authorize, get_required_permission}
+	}
 };
 
 pub struct Handler;
@@ -17,35 +25,184 @@ impl EventHandler for Handler {
 	async fn ready(&self, ctx: Context, ready: Ready) {
 		info!("{} is connected!", ready.user.name);
 
-		let guild_id = GuildId::new(CLIENT_CONFIG.discord_guild_id);
-
-		guild_id
-			.set_commands(
-				&ctx.http,
-				vec![
-					request_level::register(),
-					review::register_review(),
-					reviewer::register_add_reviewer(),
-					reviewer::register_remove_reviewer(),
-					send_level::register_send_level(),
-				]
-			)
-			.await
-			.expect("Unable to set commands");
+		for guild_config in &CLIENT_CONFIG.guilds {
+			let guild_id = GuildId::new(guild_config.discord_guild_id);
+
+			if let Err(error) = guild_id
+				.set_commands(
+					&ctx.http,
+					vec![
+						request_level::register(),
+						review::register_review(),
+						reviewer::register_add_reviewer(),
+						reviewer::register_remove_reviewer(),
+						send_level::register_send_level(),
+						queue::register_queue(),
+						request_status::register_request_status(),
+						request_manage::register_request_edit(),
+						request_manage::register_request_withdraw(),
+					]
+				)
+				.await
+			{
+				error!("Unable to set commands for guild {}: {}", guild_id, error);
+			}
+		}
 	}
 
 	async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
-		if let Interaction::Command(command) = interaction {
-			debug!("Received command interaction: {command:#?}");
-
-			match command.data.name.as_str() {
-				"request-level" => request_level::run_request_level(&ctx, &command).await,
-				"review" => review::post_level_review(&ctx, &command).await,
-				"add-reviewer" => reviewer::run_add_reviewer(&ctx, &command).await,
-				"remove-reviewer" => reviewer::run_remove_reviewer(&ctx, &command).await,
-				"send-level" => send_level::run_send_level(&ctx, &command).await,
-				_ => println!("Unreachable")
-			};
+		let services = match Services::get(&ctx).await {
+			Some(services) => services,
+			None => {
+				error!("Services are missing from the client data");
+				return;
+			}
+		};
+
+		match interaction {
+			Interaction::Command(command) => {
+				debug!("Received command interaction: {command:#?}");
+
+				let guild_config = match get_guild_config(command.guild_id) {
+					Some(guild_config) => guild_config,
+					None => {
+						invoke_ephermal(
+							"This server is not configured for RequestX.",
+							&ctx.http,
+							&command
+						)
+						.await;
+						return;
+					}
+				};
+
+				if !authorize(
+					&ctx.http,
+					&command,
+					guild_config,
+					get_required_permission(&command.data.name)
+				)
+				.await
+				{
+					return;
+				}
+
+				match command.data.name.as_str() {
+					"request-level" => {
+						request_level::run_request_level(
+							&ctx.http,
+							&services,
+							&command,
+							guild_config
+						)
+						.await
+					}
+					"review" => review::post_level_review(&ctx.http, &services, &command).await,
+					"add-reviewer" => {
+						reviewer::run_add_reviewer(&ctx.http, &services, &command, guild_config)
+							.await
+					}
+					"remove-reviewer" => {
+						reviewer::run_remove_reviewer(&ctx.http, &services, &command, guild_config)
+							.await
+					}
+					"send-level" => {
+						send_level::run_send_level(&ctx.http, &services, &command, guild_config)
+							.await
+					}
+					"queue" => queue::run_queue(&ctx.http, &services, &command, guild_config).await,
+					"request-status" => {
+						request_status::run_request_status(
+							&ctx.http,
+							&services,
+							&command,
+							guild_config
+						)
+						.await
+					}
+					"request-edit" => {
+						request_manage::run_request_edit(
+							&ctx.http,
+							&services,
+							&command,
+							guild_config
+						)
+						.await
+					}
+					"request-withdraw" => {
+						request_manage::run_request_withdraw(
+							&ctx.http,
+							&services,
+							&command,
+							guild_config
+						)
+						.await
+					}
+					_ => println!("Unreachable")
+				};
+			}
+			Interaction::Autocomplete(autocomplete) => {
+				debug!("Received autocomplete interaction: {autocomplete:#?}");
+
+				let guild_config = match get_guild_config(autocomplete.guild_id) {
+					Some(guild_config) => guild_config,
+					None => return
+				};
+
+				autocomplete::run_autocomplete(&ctx.http, &services, &autocomplete, guild_config)
+					.await;
+			}
+			Interaction::Component(component) => {
+				debug!("Received component interaction: {component:#?}");
+
+				let guild_config = match get_guild_config(component.guild_id) {
+					Some(guild_config) => guild_config,
+					None => return
+				};
+
+				match component.data.custom_id.split(':').next() {
+					Some(queue::QUEUE_CUSTOM_ID_PREFIX) => {
+						queue::run_queue_page(&ctx.http, &services, &component, guild_config).await
+					}
+					Some(request_actions::REQUEST_ACTION_CUSTOM_ID_PREFIX) => {
+						request_actions::run_request_action(
+							&ctx.http,
+							&services,
+							&component,
+							guild_config
+						)
+						.await
+					}
+					_ => warn!("Unknown component: {}", component.data.custom_id)
+				};
+			}
+			Interaction::Modal(modal) => {
+				debug!("Received modal submit interaction: {modal:#?}");
+
+				let guild_config = match get_guild_config(modal.guild_id) {
+					Some(guild_config) => guild_config,
+					None => return
+				};
+
+				match modal.data.custom_id.split(':').next() {
+					Some(review::REVIEW_MODAL_CUSTOM_ID_PREFIX) => {
+						review::run_review_modal(&ctx.http, &services, &modal, guild_config).await
+					}
+					_ => warn!("Unknown modal: {}", modal.data.custom_id)
+				};
+			}
+			_ => {}
 		}
 	}
 }
+
+fn get_guild_config(guild_id: Option<GuildId>) -> Option<&'static GuildConfig> {
+	let guild_config = guild_id.and_then(|guild_id| CLIENT_CONFIG.get_guild_config(guild_id.get()));
+	if guild_config.is_none() {
+		warn!(
+			"Received interaction from unconfigured guild: {:?}",
+			guild_id
+		);
+	}
+	guild_config
+}
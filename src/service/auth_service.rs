@@ -1,13 +1,18 @@
-use chrono::{Duration, Utc};
+use std::fmt::{Display, Formatter};
+
+use chrono::{DateTime, Duration, Utc};
 use jsonwebtoken::{decode, DecodingKey, Validation};
 use lazy_static::lazy_static;
-use log::{debug, error, warn};
+use log::{debug, error, info, warn};
 use reqwest::{
 	header::{HeaderMap, HeaderValue},
 	StatusCode
 };
 use serde::{Deserialize, Serialize};
-use tokio::sync::Mutex;
+use tokio::{
+	sync::{Mutex, RwLock},
+	time::sleep
+};
 
 use crate::{
 	config::{
@@ -17,6 +22,9 @@ use crate::{
 	model::error::auth_error::AuthError
 };
 
+const MIN_REFRESH_INTERVAL_SECS: i64 = 10;
+const MAX_REFRESH_RETRY_SECS: i64 = 300;
+
 #[derive(Debug, Serialize, Deserialize)]
 struct Claims {
 	aud: u64,
@@ -24,45 +32,181 @@ struct Claims {
 	exp: usize
 }
 
+#[derive(Debug, Clone)]
+struct Token {
+	jwt: String,
+	expires_at: DateTime<Utc>,
+	refresh_at: DateTime<Utc>
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TokenHealth {
+	pub last_refresh: Option<DateTime<Utc>>,
+	pub next_refresh: Option<DateTime<Utc>>,
+	pub consecutive_failures: u32
+}
+
+impl Display for TokenHealth {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let format_time = |time: Option<DateTime<Utc>>| {
+			time.map_or("never".to_string(), |time| time.to_rfc3339())
+		};
+		write!(
+			f,
+			"last refresh: {}, next refresh: {}, consecutive failures: {}",
+			format_time(self.last_refresh),
+			format_time(self.next_refresh),
+			self.consecutive_failures
+		)
+	}
+}
+
+pub struct TokenManager {
+	token: RwLock<Option<Token>>,
+	health: RwLock<TokenHealth>,
+	refresh_lock: Mutex<()>
+}
+
 lazy_static! {
-	pub static ref JWT: Mutex<Option<String>> = Mutex::new(None);
+	pub static ref JWT: TokenManager = TokenManager::new();
 }
 
-impl JWT {
+impl TokenManager {
+	fn new() -> Self {
+		TokenManager {
+			token: RwLock::new(None),
+			health: RwLock::new(TokenHealth::default()),
+			refresh_lock: Mutex::new(())
+		}
+	}
+
+	// A token past its refresh time is still served until it expires, so a
+	// failing or slow background refresh never blocks requests
 	pub async fn get_jwt(&self) -> Result<String, AuthError> {
-		let mut jwt_lock = JWT.lock().await;
+		match self.get_valid_jwt().await {
+			Some(jwt) => Ok(jwt),
+			None => self.refresh().await
+		}
+	}
+
+	pub async fn invalidate(&self) { *self.token.write().await = None; }
+
+	pub async fn health(&self) -> TokenHealth { self.health.read().await.clone() }
+
+	// Refreshes the token `token_buffer` minutes before it expires so requests
+	// never have to wait on authentication, backing off while RequestX is down
+	pub fn start_refresh_task(&'static self) {
+		tokio::spawn(async move {
+			loop {
+				let delay = match self.refresh_if_due().await {
+					Ok(_) => self.time_until_refresh().await,
+					Err(auth_error) => {
+						let health = self.health().await;
+						warn!("Background JWT refresh failed: {} ({})", auth_error, health);
+						Duration::seconds(
+							(MIN_REFRESH_INTERVAL_SECS << health.consecutive_failures.min(5))
+								.min(MAX_REFRESH_RETRY_SECS)
+						)
+					}
+				};
+				sleep(delay.to_std().unwrap_or_default()).await;
+			}
+		});
+	}
+
+	async fn refresh_if_due(&self) -> Result<String, AuthError> {
+		match self.get_fresh_jwt().await {
+			Some(jwt) => Ok(jwt),
+			None => self.refresh().await
+		}
+	}
 
-		if jwt_lock
+	async fn get_valid_jwt(&self) -> Option<String> {
+		self.token
+			.read()
+			.await
 			.as_ref()
-			.map_or(true, |token| Self::is_expired(token))
-		{
-			warn!("JWT is expired or null, generating new token");
-			match Self::generate_token().await {
-				Ok(jwt) => {
-					*jwt_lock = Some(jwt.clone());
-					Ok(jwt)
-				}
-				Err(auth_error) => {
-					error!("Authentication failed: {}", auth_error);
-					Err(auth_error)
-				}
+			.filter(|token| Utc::now() < token.expires_at)
+			.map(|token| token.jwt.clone())
+	}
+
+	async fn get_fresh_jwt(&self) -> Option<String> {
+		self.token
+			.read()
+			.await
+			.as_ref()
+			.filter(|token| Utc::now() < token.refresh_at)
+			.map(|token| token.jwt.clone())
+	}
+
+	async fn time_until_refresh(&self) -> Duration {
+		let refresh_at = self
+			.token
+			.read()
+			.await
+			.as_ref()
+			.map_or(Utc::now(), |token| token.refresh_at);
+		(refresh_at - Utc::now()).max(Duration::seconds(MIN_REFRESH_INTERVAL_SECS))
+	}
+
+	async fn refresh(&self) -> Result<String, AuthError> {
+		let _refresh_guard = self.refresh_lock.lock().await;
+		// Another caller may have refreshed the token while this one waited
+		if let Some(jwt) = self.get_fresh_jwt().await {
+			return Ok(jwt);
+		}
+
+		warn!("JWT is expired or null, generating new token");
+		let token = match Self::generate_token().await {
+			Ok(jwt) => Self::parse_token(jwt),
+			Err(auth_error) => Err(auth_error)
+		};
+
+		let mut health = self.health.write().await;
+		match token {
+			Ok(token) => {
+				health.last_refresh = Some(Utc::now());
+				health.next_refresh = Some(token.refresh_at);
+				health.consecutive_failures = 0;
+				info!(
+					"JWT refreshed, expires at {} ({})",
+					token.expires_at.to_rfc3339(),
+					health
+				);
+
+				let jwt = token.jwt.clone();
+				*self.token.write().await = Some(token);
+				Ok(jwt)
+			}
+			Err(auth_error) => {
+				health.next_refresh = None;
+				health.consecutive_failures = health.consecutive_failures.saturating_add(1);
+				error!("Authentication failed: {} ({})", auth_error, health);
+				Err(auth_error)
 			}
-		} else {
-			Ok(jwt_lock.as_ref().unwrap().clone())
 		}
 	}
 
-	fn is_expired(token: &str) -> bool {
-		if let Ok(token_data) = decode::<Claims>(
-			token,
-			&DecodingKey::from_secret(&AUTH_CONFIG.secret_token.as_ref()),
-			&Validation::default()
+	fn parse_token(jwt: String) -> Result<Token, AuthError> {
+		let mut validation = Validation::default();
+		validation.validate_aud = false;
+		match decode::<Claims>(
+			&jwt,
+			&DecodingKey::from_secret(AUTH_CONFIG.secret_token.as_ref()),
+			&validation
 		) {
-			return token_data.claims.exp
-				< (Utc::now() - Duration::minutes(AUTH_CONFIG.token_buffer as i64)).timestamp()
-					as usize;
-		} else {
-			true
+			Ok(token_data) => match DateTime::from_timestamp(token_data.claims.exp as i64, 0) {
+				Some(expires_at) => Ok(Token {
+					jwt,
+					expires_at,
+					refresh_at: expires_at - Duration::minutes(AUTH_CONFIG.token_buffer as i64)
+				}),
+				None => Err(AuthError::InvalidToken)
+			},
+			Err(error) => {
+				error!("Unable to decode JWT: {}", error);
+				Err(AuthError::InvalidToken)
+			}
 		}
 	}
 
@@ -93,13 +237,17 @@ impl JWT {
 		{
 			Ok(resp) => {
 				if resp.status().eq(&StatusCode::CREATED) {
-					Ok(resp
+					match resp
 						.headers()
 						.get("authorization")
-						.unwrap()
-						.to_str()
-						.unwrap()
-						.to_string())
+						.and_then(|authorization| authorization.to_str().ok())
+					{
+						Some(jwt) => Ok(jwt.to_string()),
+						None => {
+							error!("RequestX API returned no valid authorization header");
+							Err(AuthError::InvalidToken)
+						}
+					}
 				} else {
 					Err(AuthError::Unauthorized)
 				}
@@ -111,3 +259,51 @@ impl JWT {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use chrono::{Duration, Utc};
+	use jsonwebtoken::{encode, EncodingKey, Header};
+
+	use crate::{
+		config::auth_config::AUTH_CONFIG,
+		service::auth_service::{Claims, Token, TokenManager}
+	};
+
+	#[test]
+	fn token_should_be_refreshed_token_buffer_minutes_before_expiry() {
+		let expires_at = Utc::now() + Duration::hours(1);
+		let claims = Claims {
+			aud: 1,
+			iat: Utc::now().timestamp() as usize,
+			exp: expires_at.timestamp() as usize
+		};
+		let jwt = encode(
+			&Header::default(),
+			&claims,
+			&EncodingKey::from_secret(AUTH_CONFIG.secret_token.as_ref())
+		)
+		.unwrap();
+
+		let token = TokenManager::parse_token(jwt).unwrap();
+
+		assert_eq!(token.expires_at.timestamp(), expires_at.timestamp());
+		assert_eq!(
+			token.expires_at - token.refresh_at,
+			Duration::minutes(AUTH_CONFIG.token_buffer as i64)
+		);
+	}
+
+	#[tokio::test]
+	async fn token_past_refresh_time_should_be_served_until_it_expires() {
+		let token_manager = TokenManager::new();
+		*token_manager.token.write().await = Some(Token {
+			jwt: "cached".to_string(),
+			expires_at: Utc::now() + Duration::minutes(1),
+			refresh_at: Utc::now() - Duration::minutes(1)
+		});
+
+		assert_eq!(token_manager.get_jwt().await.unwrap(), "cached");
+		assert!(token_manager.get_fresh_jwt().await.is_none());
+	}
+}
@@ -1,26 +1,41 @@
-use log::warn;
+use std::sync::Arc;
+
+use log::{error, warn};
 
 use crate::{
-	config::constants::YOUTUBE_LINK_REGEX,
+	config::{client_config::GuildConfig, constants::YOUTUBE_LINK_REGEX},
 	model::{
-		error::level_request_error::LevelRequestError,
+		discord_gateway::discord_gateway_trait::DiscordGateway,
+		discord_user::DiscordUser,
+		error::{
+			discord_gateway_error::DiscordGatewayError, level_request_error::LevelRequestError
+		},
 		level_request::{
-			GetLevelRequest, LevelRequest, UpdateLevelRequestMessageId, UpdateLevelRequestThreadId
+			DeleteLevelRequest, GetLevelRequest, GetLevelRequestQueue, GetRequesterLevelRequests,
+			LevelRequest, SearchLevelRequests, UpdateLevelRequest, UpdateLevelRequestMessageId,
+			UpdateLevelRequestThreadId
 		},
 		requestx_api::{
-			level_request_data::LevelRequestData, requestx_api_client::RequestXApiClient
+			level_request_data::{LevelRequestData, LevelRequestQueueData},
+			requestx_api_trait::RequestXApi
 		}
-	}
+	},
+	util::render::{render_level_request, RenderedMessage}
 };
 
-pub struct LevelRequestService<'a> {
-	requestx_api_client: RequestXApiClient<'a>
+pub struct LevelRequestService {
+	requestx_api: Arc<dyn RequestXApi>,
+	discord_gateway: Arc<dyn DiscordGateway>
 }
 
-impl<'a> LevelRequestService<'a> {
-	pub fn new() -> Self {
+impl LevelRequestService {
+	pub fn new(
+		requestx_api: Arc<dyn RequestXApi>,
+		discord_gateway: Arc<dyn DiscordGateway>
+	) -> Self {
 		LevelRequestService {
-			requestx_api_client: RequestXApiClient::new()
+			requestx_api,
+			discord_gateway
 		}
 	}
 
@@ -28,9 +43,47 @@ impl<'a> LevelRequestService<'a> {
 		&self,
 		get_level_request: GetLevelRequest
 	) -> Result<Option<LevelRequestData>, LevelRequestError> {
+		match self.requestx_api.get_level_request(get_level_request).await {
+			Ok(resp) => Ok(resp),
+			Err(error) => Err(error)
+		}
+	}
+
+	pub async fn get_level_request_queue(
+		&self,
+		get_level_request_queue: GetLevelRequestQueue
+	) -> Result<LevelRequestQueueData, LevelRequestError> {
+		match self
+			.requestx_api
+			.get_level_request_queue(get_level_request_queue)
+			.await
+		{
+			Ok(resp) => Ok(resp),
+			Err(error) => Err(error)
+		}
+	}
+
+	pub async fn search_level_requests(
+		&self,
+		search_level_requests: SearchLevelRequests
+	) -> Result<LevelRequestQueueData, LevelRequestError> {
+		match self
+			.requestx_api
+			.search_level_requests(search_level_requests)
+			.await
+		{
+			Ok(resp) => Ok(resp),
+			Err(error) => Err(error)
+		}
+	}
+
+	pub async fn get_requester_level_requests(
+		&self,
+		get_requester_level_requests: GetRequesterLevelRequests
+	) -> Result<LevelRequestQueueData, LevelRequestError> {
 		match self
-			.requestx_api_client
-			.get_level_request(get_level_request)
+			.requestx_api
+			.get_requester_level_requests(get_requester_level_requests)
 			.await
 		{
 			Ok(resp) => Ok(resp),
@@ -44,10 +97,10 @@ impl<'a> LevelRequestService<'a> {
 	) -> Result<LevelRequestData, LevelRequestError> {
 		if !Self::is_valid_youtube_link(&level_request.youtube_video_link) {
 			warn!("Invalid link: {}", &level_request.youtube_video_link);
-			return Err(LevelRequestError::SerializeError);
+			return Err(LevelRequestError::InvalidVideoLink);
 		}
 		match self
-			.requestx_api_client
+			.requestx_api
 			.make_requestx_api_level_request(level_request)
 			.await
 		{
@@ -56,12 +109,106 @@ impl<'a> LevelRequestService<'a> {
 		}
 	}
 
+	// The request is already updated once RequestX accepts it, failing to edit
+	// the posted message is only logged
+	pub async fn edit_level_request(
+		&self,
+		guild_config: &GuildConfig,
+		update_level_request: UpdateLevelRequest
+	) -> Result<LevelRequestData, LevelRequestError> {
+		if let Some(youtube_video_link) = &update_level_request.youtube_video_link {
+			if !Self::is_valid_youtube_link(youtube_video_link) {
+				warn!("Invalid link: {}", youtube_video_link);
+				return Err(LevelRequestError::InvalidVideoLink);
+			}
+		}
+		let level_request = self
+			.requestx_api
+			.update_level_request(update_level_request)
+			.await?;
+
+		if let Some(message_id) = level_request.discord_message_id {
+			if let Err(error) = self
+				.discord_gateway
+				.edit_message(
+					guild_config.get_level_request_channel_id(&level_request),
+					message_id,
+					&render_level_request(&level_request),
+					guild_config
+				)
+				.await
+			{
+				error!("Unable to edit request message: {}", error);
+			}
+		}
+		Ok(level_request)
+	}
+
+	pub async fn withdraw_level_request(
+		&self,
+		guild_config: &GuildConfig,
+		level_request: &LevelRequestData
+	) -> Result<(), LevelRequestError> {
+		self.requestx_api
+			.delete_level_request(DeleteLevelRequest {
+				level_id: level_request.level_id,
+				discord_user_id: level_request.discord_id
+			})
+			.await?;
+
+		if let Some(thread_id) = level_request.discord_thread_id {
+			if let Err(error) = self.discord_gateway.delete_thread(thread_id).await {
+				error!("Unable to delete request thread: {}", error);
+			}
+		}
+		if let Some(message_id) = level_request.discord_message_id {
+			if let Err(error) = self
+				.discord_gateway
+				.delete_message(
+					guild_config.get_level_request_channel_id(level_request),
+					message_id
+				)
+				.await
+			{
+				error!("Unable to delete request message: {}", error);
+			}
+		}
+		Ok(())
+	}
+
+	// The request already exists once this is called, failing to store the
+	// message ID is only logged
+	pub async fn post_level_request(
+		&self,
+		guild_config: &GuildConfig,
+		level_request: &LevelRequestData,
+		message: &RenderedMessage
+	) -> Result<u64, DiscordGatewayError> {
+		let channel_id = guild_config.get_request_channel_id(level_request.level_length);
+		let message_id = self
+			.discord_gateway
+			.post_message(channel_id, message, guild_config)
+			.await?;
+
+		if let Err(error) = self
+			.update_request_message_id(UpdateLevelRequestMessageId {
+				level_id: level_request.level_id,
+				discord_message_id: message_id,
+				discord_channel_id: channel_id
+			})
+			.await
+		{
+			error!("Error updating message ID: {error:?}");
+		}
+		Ok(message_id)
+	}
+
 	pub async fn update_request_message_id(
 		&self,
 		update_level_request_message: UpdateLevelRequestMessageId
 	) -> Result<(), LevelRequestError> {
 		match self
-			.requestx_api_client
+			.requestx_api
 			.update_request_message_id(update_level_request_message)
 			.await
 		{
@@ -75,7 +222,7 @@ impl<'a> LevelRequestService<'a> {
 		update_level_thread_message: UpdateLevelRequestThreadId
 	) -> Result<(), LevelRequestError> {
 		match self
-			.requestx_api_client
+			.requestx_api
 			.update_request_thread_id(update_level_thread_message)
 			.await
 		{
@@ -84,6 +231,26 @@ impl<'a> LevelRequestService<'a> {
 		}
 	}
 
+	pub async fn create_request_thread(
+		&self,
+		user: &DiscordUser,
+		guild_config: &GuildConfig,
+		level_request: &LevelRequestData,
+		message_id: u64
+	) -> Result<u64, DiscordGatewayError> {
+		self.discord_gateway
+			.create_thread(
+				guild_config.get_level_request_channel_id(level_request),
+				message_id,
+				format!(
+					"\"{}\" ({})",
+					level_request.level_name, level_request.level_id
+				),
+				format!("Created via RequestX by: {} {}", user.name, user.id)
+			)
+			.await
+	}
+
 	fn is_valid_youtube_link(youtube_link: &str) -> bool {
 		let regex = regex::RegexBuilder::new(YOUTUBE_LINK_REGEX)
 			.case_insensitive(true)
@@ -94,3 +261,109 @@ impl<'a> LevelRequestService<'a> {
 		regex.is_match(youtube_link)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+
+	use crate::{
+		model::{
+			error::level_request_error::LevelRequestError,
+			level_request::{
+				GetLevelRequest, LevelRequest, UpdateLevelRequest, UpdateLevelRequestMessageId
+			},
+			request_score::RequestRating,
+			requestx_api::in_memory_requestx_api::InMemoryRequestXApi
+		},
+		service::level_request_service::LevelRequestService,
+		test_harness::{test_guild_config, DiscordGatewayCall, RecordingDiscordGateway}
+	};
+
+	fn test_level_request(youtube_video_link: &str) -> LevelRequest {
+		LevelRequest {
+			discord_user_id: 164072941645070336,
+			level_id: 97624039,
+			request_score: RequestRating::Four,
+			youtube_video_link: youtube_video_link.to_string(),
+			has_requested_feedback: false,
+			notify: false
+		}
+	}
+
+	#[tokio::test]
+	async fn request_level_should_store_valid_requests_only() {
+		let level_request_service = LevelRequestService::new(
+			Arc::new(InMemoryRequestXApi::default()),
+			Arc::new(RecordingDiscordGateway::default())
+		);
+
+		assert_eq!(
+			level_request_service
+				.request_level(test_level_request("not a link"))
+				.await
+				.unwrap_err(),
+			LevelRequestError::InvalidVideoLink
+		);
+		level_request_service
+			.request_level(test_level_request("https://youtu.be/dQw4w9WgXcQ"))
+			.await
+			.unwrap();
+		assert!(level_request_service
+			.get_level_request(GetLevelRequest { level_id: 97624039 })
+			.await
+			.unwrap()
+			.is_some());
+	}
+
+	#[tokio::test]
+	async fn edit_level_request_should_edit_the_message_where_it_was_posted() {
+		let discord_gateway = Arc::new(RecordingDiscordGateway::default());
+		let level_request_service = LevelRequestService::new(
+			Arc::new(InMemoryRequestXApi::default()),
+			discord_gateway.clone()
+		);
+		level_request_service
+			.request_level(test_level_request("https://youtu.be/dQw4w9WgXcQ"))
+			.await
+			.unwrap();
+		level_request_service
+			.update_request_message_id(UpdateLevelRequestMessageId {
+				level_id: 97624039,
+				discord_message_id: 300,
+				discord_channel_id: 7
+			})
+			.await
+			.unwrap();
+		let update_level_request = |youtube_video_link: &str| UpdateLevelRequest {
+			level_id: 97624039,
+			discord_user_id: 164072941645070336,
+			request_score: None,
+			youtube_video_link: Some(youtube_video_link.to_string()),
+			has_requested_feedback: None,
+			notify: None
+		};
+
+		assert_eq!(
+			level_request_service
+				.edit_level_request(test_guild_config(), update_level_request("not a link"))
+				.await
+				.unwrap_err(),
+			LevelRequestError::InvalidVideoLink
+		);
+		level_request_service
+			.edit_level_request(
+				test_guild_config(),
+				update_level_request("https://youtu.be/9bZkp7q19f0")
+			)
+			.await
+			.unwrap();
+
+		assert_eq!(
+			*discord_gateway.calls.lock().unwrap(),
+			vec![DiscordGatewayCall::EditMessage {
+				channel_id: 7,
+				message_id: 300
+			}]
+		);
+	}
+}
@@ -1,41 +1,65 @@
+use std::sync::Arc;
+
 use log::error;
-use serenity::all::{
-	ChannelId, CommandInteraction, Context, EditMessage, Mentionable, MessageBuilder, UserId
-};
 
 use crate::{
-	config::client_config::CLIENT_CONFIG,
+	config::client_config::GuildConfig,
 	model::{
-		error::level_request_error::LevelRequestError,
-		level_request::{GetLevelRequest, GetLevelReview, UpdateLevelRequestThreadId},
-		level_review::LevelReview,
+		discord_gateway::discord_gateway_trait::DiscordGateway,
+		discord_user::DiscordUser,
+		error::{level_request_error::LevelRequestError, requestx_error::RequestXError},
+		level_request::{
+			GetLevelRequest, GetLevelReview, GetLevelReviews, UpdateLevelRequestThreadId
+		},
+		level_review::{LevelReview, LevelReviewOutcome},
+		permission::Permission,
 		requestx_api::{
 			level_review_data::LevelReviewData, level_review_error::LevelReviewError,
-			requestx_api_client::RequestXApiClient
+			requestx_api_trait::RequestXApi
 		}
 	},
 	service::level_request_service::LevelRequestService,
-	util::discord::create_thread
+	util::render::render_level_review
 };
 
-pub struct LevelReviewService<'a> {
-	requestx_api_client: RequestXApiClient<'a>
+pub struct LevelReviewService {
+	requestx_api: Arc<dyn RequestXApi>,
+	discord_gateway: Arc<dyn DiscordGateway>,
+	level_request_service: LevelRequestService
 }
 
-impl<'a> LevelReviewService<'a> {
-	pub fn new() -> Self {
+impl LevelReviewService {
+	pub fn new(
+		requestx_api: Arc<dyn RequestXApi>,
+		discord_gateway: Arc<dyn DiscordGateway>
+	) -> Self {
 		LevelReviewService {
-			requestx_api_client: RequestXApiClient::new()
+			level_request_service: LevelRequestService::new(
+				requestx_api.clone(),
+				discord_gateway.clone()
+			),
+			requestx_api,
+			discord_gateway
 		}
 	}
 
 	pub async fn get_level_review(
 		&self,
 		get_level_review: GetLevelReview
+	) -> Result<Option<LevelReviewData>, LevelReviewError> {
+		match self.requestx_api.get_level_review(get_level_review).await {
+			Ok(resp) => Ok(resp),
+			Err(error) => Err(error)
+		}
+	}
+
+	pub async fn get_level_review_once(
+		&self,
+		get_level_review: GetLevelReview
 	) -> Result<Option<LevelReviewData>, LevelReviewError> {
 		match self
-			.requestx_api_client
-			.get_level_review(get_level_review)
+			.requestx_api
+			.get_level_review_once(get_level_review)
 			.await
 		{
 			Ok(resp) => Ok(resp),
@@ -43,26 +67,37 @@ impl<'a> LevelReviewService<'a> {
 		}
 	}
 
+	pub async fn get_level_reviews(
+		&self,
+		get_level_reviews: GetLevelReviews
+	) -> Result<Vec<LevelReviewData>, LevelReviewError> {
+		match self.requestx_api.get_level_reviews(get_level_reviews).await {
+			Ok(resp) => Ok(resp),
+			Err(error) => Err(error)
+		}
+	}
+
 	pub async fn review_level(
 		&self,
-		ctx: &Context,
-		command: &CommandInteraction,
+		reviewer: &DiscordUser,
+		reviewer_permission: Option<Permission>,
+		guild_config: &GuildConfig,
 		level_id: u64,
-		reviewer_discord_user_id: u64,
 		review_contents: String
-	) -> Result<String, LevelReviewError> {
+	) -> Result<LevelReviewOutcome, LevelReviewError> {
+		let reviewer_discord_user_id = reviewer.id;
 		let get_level_request = GetLevelRequest { level_id };
-		let level_request_service = LevelRequestService::new();
-		match level_request_service
+		match self
+			.level_request_service
 			.get_level_request(get_level_request)
 			.await
 		{
 			Ok(potential_level_request) => {
 				if let Some(level_request) = potential_level_request {
 					if !level_request.has_requested_feedback
-						&& reviewer_discord_user_id.ne(&CLIENT_CONFIG.discord_bot_admin_id)
+						&& reviewer_permission != Some(Permission::Admin)
 					{
-						return Ok("The user has not requested feedback for this level".to_string());
+						return Ok(LevelReviewOutcome::FeedbackNotRequested);
 					}
 					if let Some(level_request_message_id) = level_request.discord_message_id {
 						// Request Message Exists
@@ -76,69 +111,69 @@ impl<'a> LevelReviewService<'a> {
 								if let Some(thread) = level_request.discord_thread_id {
 									thread_id = thread;
 								} else {
-									if let Ok(thread) = create_thread(
-										&ctx,
-										&command,
-										level_request_message_id,
-										&level_request
-									)
-									.await
+									match self
+										.level_request_service
+										.create_request_thread(
+											reviewer,
+											guild_config,
+											&level_request,
+											level_request_message_id
+										)
+										.await
 									{
-										thread_id = thread;
-
-										let update_level_request_thread_id =
-											UpdateLevelRequestThreadId {
-												level_id: level_request.level_id,
-												discord_thread_id: thread_id
-											};
-
-										if let Err(update_level_request_thread_id_error) =
-											level_request_service
-												.update_request_thread_id(
-													update_level_request_thread_id
-												)
-												.await
-										{
+										Ok(thread) => thread_id = thread,
+										Err(create_thread_error) => {
 											error!(
-												"Unable to update level request thread ID: {}",
-												update_level_request_thread_id_error
+												"Unable to create request thread: {}",
+												create_thread_error
 											);
-											return Err(LevelReviewError::RequestError);
+											return Err(create_thread_error.into());
 										}
-									} else {
-										return Err(LevelReviewError::RequestError);
 									}
-								}
 
-								let mut review_message = MessageBuilder::new();
-								review_message
-									.push_bold_line(format!(
-										"Review by {}",
-										command.user.id.mention()
-									))
-									.push_line("")
-									.push_quote_line_safe(&review_contents);
-
-								if level_request.notify {
-									review_message.push_line("");
-									review_message.push_line(format!(
-										"{}",
-										UserId::new(level_request.discord_id).mention()
-									));
+									let update_level_request_thread_id =
+										UpdateLevelRequestThreadId {
+											level_id: level_request.level_id,
+											discord_thread_id: thread_id
+										};
+
+									if let Err(update_level_request_thread_id_error) = self
+										.level_request_service
+										.update_request_thread_id(update_level_request_thread_id)
+										.await
+									{
+										error!(
+											"Unable to update level request thread ID: {}",
+											update_level_request_thread_id_error
+										);
+										return Err(LevelReviewError::RequestX(
+											RequestXError::RequestError
+										));
+									}
 								}
 
+								let review_message = render_level_review(
+									&level_request,
+									&self.discord_gateway.mention_user(reviewer_discord_user_id),
+									&review_contents
+								);
+
 								let review_discord_message_id: u64;
+								let level_review_outcome;
 								if let Some(existing_level_review) = potential_level_review {
+									level_review_outcome = LevelReviewOutcome::Edited;
 									// EXISTING LEVEL REVIEW
 									if let Some(review_message_id) =
 										existing_level_review.discord_message_id
 									{
 										review_discord_message_id = review_message_id;
-										if let Err(edit_message_error) = ChannelId::new(thread_id)
+										if let Err(edit_message_error) = self
+											.discord_gateway
 											.edit_message(
-												&ctx.http,
+												thread_id,
 												review_message_id,
-												EditMessage::new().content(&review_message.build())
+												&review_message,
+												guild_config
 											)
 											.await
 										{
@@ -146,25 +181,29 @@ impl<'a> LevelReviewService<'a> {
 												"Unable to edit review message: {}",
 												edit_message_error
 											);
-											return Err(LevelReviewError::RequestError);
+											return Err(edit_message_error.into());
 										};
 									} else {
 										// There is probably a database inconsistency if this
 										// happens
-										return Err(LevelReviewError::RequestError);
+										return Err(LevelReviewError::RequestX(
+											RequestXError::RequestError
+										));
 									}
 								} else {
-									match ChannelId::new(thread_id)
-										.say(&ctx.http, &review_message.build())
+									level_review_outcome = LevelReviewOutcome::Posted;
+									match self
+										.discord_gateway
+										.post_message(thread_id, &review_message, guild_config)
 										.await
 									{
-										Ok(message) => review_discord_message_id = message.id.get(),
+										Ok(message_id) => review_discord_message_id = message_id,
 										Err(send_level_review_error) => {
 											error!(
 												"Unable to send level review to Discord: {}",
 												send_level_review_error
 											);
-											return Err(LevelReviewError::RequestError);
+											return Err(send_level_review_error.into());
 										}
 									};
 								};
@@ -180,24 +219,26 @@ impl<'a> LevelReviewService<'a> {
 								{
 									Err(save_level_review_error)
 								} else {
-									Ok("Review submitted".to_string())
+									Ok(level_review_outcome)
 								}
 							}
 							Err(level_review_error) => Err(level_review_error)
 						}
 					} else {
-						Err(LevelReviewError::RequestError)
+						Err(LevelReviewError::RequestX(RequestXError::RequestError))
 					}
 				} else {
 					Err(LevelReviewError::LevelRequestDoesNotExists)
 				}
 			}
 			Err(error) => match error {
-				LevelRequestError::RequestError => Err(LevelReviewError::RequestError),
-				LevelRequestError::SerializeError => Err(LevelReviewError::RequestError),
-				LevelRequestError::RequestXApiError => Err(LevelReviewError::RequestXApiError),
-				LevelRequestError::LevelRequestExists => {
-					unreachable!()
+				LevelRequestError::RequestX(requestx_error) => Err(requestx_error.into()),
+				LevelRequestError::LevelRequestExists | LevelRequestError::InvalidVideoLink => {
+					error!(
+						"Unexpected level request error while reviewing {}: {}",
+						level_id, error
+					);
+					Err(RequestXError::RequestError.into())
 				}
 			}
 		}
@@ -208,7 +249,7 @@ impl<'a> LevelReviewService<'a> {
 		level_review: &LevelReview
 	) -> Result<LevelReviewData, LevelReviewError> {
 		match self
-			.requestx_api_client
+			.requestx_api
 			.make_requestx_api_level_review_request(level_review)
 			.await
 		{
@@ -217,3 +258,94 @@ impl<'a> LevelReviewService<'a> {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+
+	use crate::{
+		model::{
+			discord_user::DiscordUser,
+			level_request::{LevelRequest, UpdateLevelRequestMessageId},
+			level_review::LevelReviewOutcome,
+			permission::Permission,
+			request_score::RequestRating,
+			requestx_api::{
+				in_memory_requestx_api::InMemoryRequestXApi, requestx_api_trait::RequestXApi
+			}
+		},
+		service::level_review_service::LevelReviewService,
+		test_harness::{
+			test_guild_config, DiscordGatewayCall, RecordingDiscordGateway, TEST_USER_ID
+		}
+	};
+
+	#[tokio::test]
+	async fn review_should_open_thread_once_and_edit_existing_review() {
+		let requestx_api = Arc::new(InMemoryRequestXApi::default());
+		let discord_gateway = Arc::new(RecordingDiscordGateway::default());
+		requestx_api
+			.make_requestx_api_level_request(LevelRequest {
+				discord_user_id: TEST_USER_ID,
+				level_id: 97624039,
+				request_score: RequestRating::Four,
+				youtube_video_link: "https://youtu.be/dQw4w9WgXcQ".to_string(),
+				has_requested_feedback: true,
+				notify: false
+			})
+			.await
+			.unwrap();
+		requestx_api
+			.update_request_message_id(UpdateLevelRequestMessageId {
+				level_id: 97624039,
+				discord_message_id: 300,
+				discord_channel_id: 1
+			})
+			.await
+			.unwrap();
+		let level_review_service =
+			LevelReviewService::new(requestx_api.clone(), discord_gateway.clone());
+		let reviewer = DiscordUser {
+			id: 555,
+			name: "reviewer".to_string()
+		};
+
+		for (review_contents, level_review_outcome) in [
+			("Nice level", LevelReviewOutcome::Posted),
+			("Nice level, fix the sync", LevelReviewOutcome::Edited)
+		] {
+			assert_eq!(
+				level_review_service
+					.review_level(
+						&reviewer,
+						Some(Permission::Reviewer),
+						test_guild_config(),
+						97624039,
+						review_contents.to_string()
+					)
+					.await
+					.unwrap(),
+				level_review_outcome
+			);
+		}
+
+		assert_eq!(
+			*discord_gateway.calls.lock().unwrap(),
+			vec![
+				DiscordGatewayCall::CreateThread {
+					channel_id: 1,
+					message_id: 300,
+					thread_id: 1000
+				},
+				DiscordGatewayCall::PostMessage {
+					channel_id: 1000,
+					message_id: 1001
+				},
+				DiscordGatewayCall::EditMessage {
+					channel_id: 1000,
+					message_id: 1001
+				}
+			]
+		);
+	}
+}
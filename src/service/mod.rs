@@ -3,3 +3,4 @@ pub mod level_request_service;
 pub mod level_review_service;
 pub mod moderator_service;
 pub mod reviewer_service;
+pub mod services;
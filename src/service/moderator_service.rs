@@ -1,74 +1,103 @@
+use std::sync::Arc;
+
 use log::error;
-use serenity::{all::CommandInteraction, client::Context};
 
 use crate::{
+	config::client_config::GuildConfig,
 	model::{
+		discord_gateway::discord_gateway_trait::DiscordGateway,
+		discord_user::DiscordUser,
+		error::{discord_gateway_error::DiscordGatewayError, requestx_error::RequestXError},
 		level_request::UpdateLevelRequestThreadId,
 		moderator::Moderator,
 		requestx_api::{
 			level_request_data::LevelRequestData, moderator_data::ModeratorError,
-			requestx_api_client::RequestXApiClient
+			requestx_api_trait::RequestXApi
 		}
 	},
 	service::level_request_service::LevelRequestService,
-	util::discord::create_thread
+	util::render::render_send_level
 };
 
-pub struct ModeratorService<'a> {
-	requestx_api_client: RequestXApiClient<'a>
+pub struct ModeratorService {
+	requestx_api: Arc<dyn RequestXApi>,
+	discord_gateway: Arc<dyn DiscordGateway>,
+	level_request_service: LevelRequestService
 }
 
-impl<'a> ModeratorService<'a> {
-	pub fn new() -> Self {
+impl ModeratorService {
+	pub fn new(
+		requestx_api: Arc<dyn RequestXApi>,
+		discord_gateway: Arc<dyn DiscordGateway>
+	) -> Self {
 		ModeratorService {
-			requestx_api_client: RequestXApiClient::new()
+			level_request_service: LevelRequestService::new(
+				requestx_api.clone(),
+				discord_gateway.clone()
+			),
+			requestx_api,
+			discord_gateway
 		}
 	}
 
 	pub async fn send_level(
 		&self,
-		ctx: &Context,
-		command: &CommandInteraction,
+		moderator: &DiscordUser,
+		guild_config: &GuildConfig,
 		send_level_request: Moderator
 	) -> Result<LevelRequestData, ModeratorError> {
 		match self
-			.requestx_api_client
+			.requestx_api
 			.make_send_level_request(send_level_request)
 			.await
 		{
 			Ok(mut level_request_data) => {
 				let thread_id;
 
-				if let None = level_request_data.discord_thread_id {
-					if let Ok(thread) = create_thread(
-						ctx,
-						&command,
-						level_request_data.discord_message_id.unwrap(),
-						&level_request_data
-					)
-					.await
-					{
-						thread_id = thread;
-						level_request_data.discord_thread_id = Some(thread_id);
-
-						let level_request_service = LevelRequestService::new();
-						let update_level_request_thread_id = UpdateLevelRequestThreadId {
-							level_id: level_request_data.level_id,
-							discord_thread_id: thread_id
-						};
-
-						if let Err(update_level_request_thread_id_error) = level_request_service
-							.update_request_thread_id(update_level_request_thread_id)
-							.await
-						{
+				if level_request_data.discord_thread_id.is_none() {
+					let discord_message_id = match level_request_data.discord_message_id {
+						Some(discord_message_id) => discord_message_id,
+						None => {
 							error!(
-								"Unable to update level request thread ID: {}",
-								update_level_request_thread_id_error
+								"Level request {} has no Discord message to create a thread from",
+								level_request_data.level_id
 							);
-							return Err(ModeratorError::RequestError);
+							return Err(ModeratorError::RequestX(RequestXError::RequestError));
+						}
+					};
+					match self
+						.level_request_service
+						.create_request_thread(
+							moderator,
+							guild_config,
+							&level_request_data,
+							discord_message_id
+						)
+						.await
+					{
+						Ok(thread) => thread_id = thread,
+						Err(create_thread_error) => {
+							error!("Unable to create request thread: {}", create_thread_error);
+							return Err(create_thread_error.into());
 						}
-					} else {
-						return Err(ModeratorError::RequestError);
+					}
+					level_request_data.discord_thread_id = Some(thread_id);
+
+					let update_level_request_thread_id = UpdateLevelRequestThreadId {
+						level_id: level_request_data.level_id,
+						discord_thread_id: thread_id
+					};
+
+					if let Err(update_level_request_thread_id_error) = self
+						.level_request_service
+						.update_request_thread_id(update_level_request_thread_id)
+						.await
+					{
+						error!(
+							"Unable to update level request thread ID: {}",
+							update_level_request_thread_id_error
+						);
+						return Err(ModeratorError::RequestX(RequestXError::RequestError));
 					}
 				}
 				Ok(level_request_data)
@@ -76,4 +105,158 @@ impl<'a> ModeratorService<'a> {
 			Err(send_level_error) => Err(send_level_error)
 		}
 	}
+
+	// Posted into the request thread, which send_level opens when missing
+	pub async fn post_send_level(
+		&self,
+		guild_config: &GuildConfig,
+		level_request_data: &LevelRequestData,
+		send_level_request: &Moderator
+	) -> Result<u64, DiscordGatewayError> {
+		let channel_id = level_request_data
+			.discord_thread_id
+			.unwrap_or(guild_config.get_level_request_channel_id(level_request_data));
+		self.discord_gateway
+			.post_message(
+				channel_id,
+				&render_send_level(level_request_data, send_level_request),
+				guild_config
+			)
+			.await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+
+	use crate::{
+		config::client_config::GuildConfig,
+		model::{
+			discord_user::DiscordUser,
+			level_request::LevelRequest,
+			moderator::{Moderator, SuggestedRating, SuggestedScore},
+			request_score::{LevelLength, RequestRating},
+			requestx_api::in_memory_requestx_api::InMemoryRequestXApi
+		},
+		service::services::Services,
+		test_harness::{
+			test_guild_config, DiscordGatewayCall, RecordingDiscordGateway, TEST_USER_ID
+		},
+		util::render::render_level_request
+	};
+
+	// Requests a level of the given length, then sends it like a moderator
+	// would once it has been posted
+	async fn request_and_send_level(
+		services: &Services,
+		guild_config: &GuildConfig,
+		level_length: LevelLength
+	) {
+		let mut level_request = services
+			.level_request_service
+			.request_level(LevelRequest {
+				discord_user_id: TEST_USER_ID,
+				level_id: 97624039,
+				request_score: RequestRating::Four,
+				youtube_video_link: "https://youtu.be/dQw4w9WgXcQ".to_string(),
+				has_requested_feedback: false,
+				notify: false
+			})
+			.await
+			.unwrap();
+		level_request.level_length = level_length;
+		services
+			.level_request_service
+			.post_level_request(
+				guild_config,
+				&level_request,
+				&render_level_request(&level_request)
+			)
+			.await
+			.unwrap();
+
+		let send_level_request = Moderator {
+			level_id: 97624039,
+			suggested_score: SuggestedScore::Four,
+			suggested_rating: SuggestedRating::Feature
+		};
+		let moderator = DiscordUser {
+			id: 555,
+			name: "moderator".to_string()
+		};
+		let level_request = services
+			.moderator_service
+			.send_level(&moderator, guild_config, send_level_request)
+			.await
+			.unwrap();
+		services
+			.moderator_service
+			.post_send_level(guild_config, &level_request, &send_level_request)
+			.await
+			.unwrap();
+	}
+
+	#[tokio::test]
+	async fn request_and_verdict_should_be_posted_through_the_gateway() {
+		let discord_gateway = Arc::new(RecordingDiscordGateway::default());
+		let services = Services::new(
+			Arc::new(InMemoryRequestXApi::default()),
+			discord_gateway.clone()
+		);
+		let guild_config = test_guild_config();
+
+		request_and_send_level(&services, guild_config, LevelLength::Medium).await;
+
+		assert_eq!(
+			*discord_gateway.calls.lock().unwrap(),
+			vec![
+				DiscordGatewayCall::PostMessage {
+					channel_id: guild_config.discord_request_channel_id,
+					message_id: 1000
+				},
+				DiscordGatewayCall::CreateThread {
+					channel_id: guild_config.discord_request_channel_id,
+					message_id: 1000,
+					thread_id: 1001
+				},
+				DiscordGatewayCall::PostMessage {
+					channel_id: 1001,
+					message_id: 1002
+				}
+			]
+		);
+	}
+
+	#[tokio::test]
+	async fn platformer_requests_should_be_routed_to_their_own_channel() {
+		let discord_gateway = Arc::new(RecordingDiscordGateway::default());
+		let services = Services::new(
+			Arc::new(InMemoryRequestXApi::default()),
+			discord_gateway.clone()
+		);
+		let mut guild_config = test_guild_config().clone();
+		guild_config.discord_level_length_channel_ids.platformer = Some(9);
+
+		request_and_send_level(&services, &guild_config, LevelLength::Platformer).await;
+
+		assert_eq!(
+			*discord_gateway.calls.lock().unwrap(),
+			vec![
+				DiscordGatewayCall::PostMessage {
+					channel_id: 9,
+					message_id: 1000
+				},
+				DiscordGatewayCall::CreateThread {
+					channel_id: 9,
+					message_id: 1000,
+					thread_id: 1001
+				},
+				DiscordGatewayCall::PostMessage {
+					channel_id: 1001,
+					message_id: 1002
+				}
+			]
+		);
+	}
 }
@@ -1,51 +1,61 @@
+use std::sync::Arc;
+
 use log::error;
-use serenity::all::{Context, GuildId, Member, User};
 
 use crate::{
-	config::client_config::CLIENT_CONFIG,
+	config::client_config::GuildConfig,
 	model::{
-		requestx_api::{requestx_api_client::RequestXApiClient, reviewer_data::ReviewerError},
+		discord_gateway::discord_gateway_trait::DiscordGateway,
+		discord_user::DiscordUser,
+		requestx_api::{requestx_api_trait::RequestXApi, reviewer_data::ReviewerError},
 		reviewer::{AddReviewerRequest, RemoveReviewerRequest}
 	}
 };
 
-pub struct ReviewerService<'a> {
-	requestx_api_client: RequestXApiClient<'a>
+pub struct ReviewerService {
+	requestx_api: Arc<dyn RequestXApi>,
+	discord_gateway: Arc<dyn DiscordGateway>
 }
 
-impl<'a> ReviewerService<'a> {
-	pub fn new() -> Self {
+impl ReviewerService {
+	pub fn new(
+		requestx_api: Arc<dyn RequestXApi>,
+		discord_gateway: Arc<dyn DiscordGateway>
+	) -> Self {
 		ReviewerService {
-			requestx_api_client: RequestXApiClient::new()
+			requestx_api,
+			discord_gateway
 		}
 	}
 
 	pub async fn create_reviewer(
 		&self,
-		ctx: &Context,
-		discord_user: &User
+		guild_config: &GuildConfig,
+		discord_user: &DiscordUser
 	) -> Result<(), ReviewerError> {
 		let add_reviewer_request = AddReviewerRequest {
-			reviewer_discord_id: discord_user.id.get()
+			reviewer_discord_id: discord_user.id
 		};
 
 		match self
-			.requestx_api_client
+			.requestx_api
 			.make_add_reviewer_request(add_reviewer_request)
 			.await
 		{
 			Ok(()) => {
-				let mut member = Member::default();
-				member.user = discord_user.clone();
-				member.guild_id = GuildId::from(CLIENT_CONFIG.discord_guild_id);
-				match member
-					.add_role(&ctx.http, CLIENT_CONFIG.discord_reviewer_role_id)
+				match self
+					.discord_gateway
+					.add_role(
+						guild_config.discord_guild_id,
+						discord_user.id,
+						guild_config.discord_reviewer_role_id
+					)
 					.await
 				{
 					Ok(()) => Ok(()),
 					Err(error) => {
 						error!("Unable to add reviewer: {}", error);
-						Err(ReviewerError::RequestError)
+						Err(error.into())
 					}
 				}
 			}
@@ -55,30 +65,32 @@ impl<'a> ReviewerService<'a> {
 
 	pub async fn remove_reviewer(
 		&self,
-		ctx: &Context,
-		discord_user: &User
+		guild_config: &GuildConfig,
+		discord_user: &DiscordUser
 	) -> Result<(), ReviewerError> {
 		let remove_reviewer_request = RemoveReviewerRequest {
-			reviewer_discord_id: discord_user.id.get()
+			reviewer_discord_id: discord_user.id
 		};
 
 		match self
-			.requestx_api_client
+			.requestx_api
 			.make_remove_reviewer_request(remove_reviewer_request)
 			.await
 		{
 			Ok(()) => {
-				let mut member = Member::default();
-				member.user = discord_user.clone();
-				member.guild_id = GuildId::from(CLIENT_CONFIG.discord_guild_id);
-				match member
-					.remove_role(&ctx.http, CLIENT_CONFIG.discord_reviewer_role_id)
+				match self
+					.discord_gateway
+					.remove_role(
+						guild_config.discord_guild_id,
+						discord_user.id,
+						guild_config.discord_reviewer_role_id
+					)
 					.await
 				{
 					Ok(()) => Ok(()),
 					Err(error) => {
 						error!("Unable to remove reviewer: {}", error);
-						Err(ReviewerError::RequestError)
+						Err(error.into())
 					}
 				}
 			}
@@ -0,0 +1,51 @@
+use std::sync::Arc;
+
+use serenity::{all::Context, prelude::TypeMapKey};
+
+use crate::{
+	model::{
+		discord_gateway::discord_gateway_trait::DiscordGateway,
+		requestx_api::requestx_api_trait::RequestXApi
+	},
+	service::{
+		level_request_service::LevelRequestService, level_review_service::LevelReviewService,
+		moderator_service::ModeratorService, reviewer_service::ReviewerService
+	}
+};
+
+// Every service shares one RequestXApi so HTTP connections are pooled across
+// commands and the backend can be swapped out for an in-memory one
+pub struct Services {
+	pub level_request_service: LevelRequestService,
+	pub level_review_service: LevelReviewService,
+	pub moderator_service: ModeratorService,
+	pub reviewer_service: ReviewerService
+}
+
+impl TypeMapKey for Services {
+	type Value = Arc<Services>;
+}
+
+impl Services {
+	pub fn new(
+		requestx_api: Arc<dyn RequestXApi>,
+		discord_gateway: Arc<dyn DiscordGateway>
+	) -> Self {
+		Services {
+			level_request_service: LevelRequestService::new(
+				requestx_api.clone(),
+				discord_gateway.clone()
+			),
+			level_review_service: LevelReviewService::new(
+				requestx_api.clone(),
+				discord_gateway.clone()
+			),
+			moderator_service: ModeratorService::new(requestx_api.clone(), discord_gateway.clone()),
+			reviewer_service: ReviewerService::new(requestx_api, discord_gateway)
+		}
+	}
+
+	pub async fn get(ctx: &Context) -> Option<Arc<Services>> {
+		ctx.data.read().await.get::<Services>().cloned()
+	}
+}
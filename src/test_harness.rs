@@ -0,0 +1,425 @@
+use std::sync::{
+	atomic::{AtomicU64, Ordering},
+	Arc, Mutex
+};
+
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use httpmock::{
+	Method::{PATCH, POST},
+	Mock, MockServer
+};
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::{json, Value};
+use serenity::all::{
+	ApplicationId, CommandInteraction, ComponentInteraction, Http, HttpBuilder, ModalInteraction
+};
+
+use crate::{
+	config::{
+		auth_config::AUTH_CONFIG, client_config::GuildConfig, common_config::APP_CONFIG,
+		requestx_api_config::REQUESTX_API_CONFIG
+	},
+	model::{
+		discord_gateway::{
+			discord_gateway_trait::DiscordGateway, serenity_discord_gateway::SerenityDiscordGateway
+		},
+		error::discord_gateway_error::DiscordGatewayError,
+		requestx_api::requestx_api_client::RequestXApiClient
+	},
+	service::services::Services,
+	util::render::RenderedMessage
+};
+
+pub const TEST_USER_ID: u64 = 164072941645070336;
+pub const TEST_REVIEWER_ROLE_ID: &str = "1";
+pub const TEST_MODERATOR_ROLE_ID: &str = "3";
+
+// The standalone httpmock server stands in for both RequestX and Discord's REST
+// API, only one test can hold it at a time so the mocks never interfere
+pub async fn init_mock_server() -> MockServer {
+	let url = url::Url::parse(&REQUESTX_API_CONFIG.base_url).unwrap();
+	let host = url.host_str().unwrap();
+	let port = url.port().unwrap();
+	let server = MockServer::connect_async(&format!("{}:{}", host, port)).await;
+	let claims = json!({
+		"aud": 1,
+		"iat": Utc::now().timestamp(),
+		"exp": (Utc::now() + Duration::hours(1)).timestamp()
+	});
+	let jwt = encode(
+		&Header::default(),
+		&claims,
+		&EncodingKey::from_secret(AUTH_CONFIG.secret_token.as_ref())
+	)
+	.unwrap();
+	server
+		.mock_async(|when, then| {
+			when.method(POST).path(&*REQUESTX_API_CONFIG.paths.auth);
+			then.status(201).header("authorization", jwt);
+		})
+		.await;
+	server
+}
+
+pub fn test_http(server: &MockServer) -> Arc<Http> {
+	Arc::new(
+		HttpBuilder::new(&APP_CONFIG.client_config.discord_bot_token)
+			.proxy(server.base_url())
+			.application_id(ApplicationId::new(APP_CONFIG.client_config.discord_app_id))
+			.ratelimiter_disabled(true)
+			.build()
+	)
+}
+
+pub fn test_services(server: &MockServer) -> Services {
+	Services::new(
+		Arc::new(RequestXApiClient::new()),
+		Arc::new(SerenityDiscordGateway::new(test_http(server)))
+	)
+}
+
+pub fn test_guild_config() -> &'static GuildConfig { &APP_CONFIG.client_config.guilds[0] }
+
+pub fn test_user() -> Value {
+	json!({
+		"id": TEST_USER_ID.to_string(),
+		"username": "someuser",
+		"discriminator": "0",
+		"global_name": null,
+		"avatar": null
+	})
+}
+
+pub fn test_member(roles: Value) -> Value {
+	json!({
+		"user": test_user(),
+		"roles": roles,
+		"joined_at": "2024-01-01T00:00:00.000000+00:00",
+		"deaf": false,
+		"mute": false,
+		"flags": 0
+	})
+}
+
+pub fn command_interaction(name: &str, options: Value, resolved: Value) -> CommandInteraction {
+	serde_json::from_value(json!({
+		"id": "100",
+		"application_id": APP_CONFIG.client_config.discord_app_id.to_string(),
+		"type": 2,
+		"data": {
+			"id": "200",
+			"name": name,
+			"type": 1,
+			"options": options,
+			"resolved": resolved
+		},
+		"guild_id": test_guild_config().discord_guild_id.to_string(),
+		"channel_id": test_guild_config().discord_request_channel_id.to_string(),
+		"member": test_member(json!([])),
+		"token": "interaction-token",
+		"version": 1,
+		"locale": "en-US",
+		"entitlements": [],
+		"attachment_size_limit": 8388608
+	}))
+	.unwrap()
+}
+
+// A button click when `values` is empty, otherwise a string select, on an
+// ephemeral message posted by the bot
+pub fn component_interaction(custom_id: &str, values: Value, roles: Value) -> ComponentInteraction {
+	let is_select = values.as_array().is_some_and(|values| !values.is_empty());
+	serde_json::from_value(json!({
+		"id": "100",
+		"application_id": APP_CONFIG.client_config.discord_app_id.to_string(),
+		"type": 3,
+		"data": {
+			"custom_id": custom_id,
+			"component_type": if is_select { 3 } else { 2 },
+			"values": values
+		},
+		"message": discord_message(test_guild_config().discord_request_channel_id, 400),
+		"guild_id": test_guild_config().discord_guild_id.to_string(),
+		"channel_id": test_guild_config().discord_request_channel_id.to_string(),
+		"member": test_member(roles),
+		"token": "interaction-token",
+		"version": 1,
+		"locale": "en-US",
+		"entitlements": [],
+		"attachment_size_limit": 8388608
+	}))
+	.unwrap()
+}
+
+// A modal submission with one text input, `roles` decides which permission the
+// submitting member resolves to
+pub fn modal_interaction(
+	custom_id: &str,
+	input_custom_id: &str,
+	value: &str,
+	roles: Value
+) -> ModalInteraction {
+	serde_json::from_value(json!({
+		"id": "100",
+		"application_id": APP_CONFIG.client_config.discord_app_id.to_string(),
+		"type": 5,
+		"data": {
+			"custom_id": custom_id,
+			"components": [{
+				"type": 1,
+				"components": [{
+					"type": 4,
+					"custom_id": input_custom_id,
+					"style": 2,
+					"value": value
+				}]
+			}]
+		},
+		"guild_id": test_guild_config().discord_guild_id.to_string(),
+		"channel_id": test_guild_config().discord_request_channel_id.to_string(),
+		"member": test_member(roles),
+		"token": "interaction-token",
+		"version": 1,
+		"locale": "en-US",
+		"entitlements": [],
+		"attachment_size_limit": 8388608
+	}))
+	.unwrap()
+}
+
+pub fn discord_message(channel_id: u64, message_id: u64) -> Value {
+	json!({
+		"id": message_id.to_string(),
+		"channel_id": channel_id.to_string(),
+		"author": test_user(),
+		"content": "",
+		"timestamp": "2024-01-01T00:00:00.000000+00:00",
+		"edited_timestamp": null,
+		"tts": false,
+		"mention_everyone": false,
+		"mentions": [],
+		"mention_roles": [],
+		"attachments": [],
+		"embeds": [],
+		"pinned": false,
+		"type": 0
+	})
+}
+
+// Responds to the interaction callback and records the content shown to the
+// user
+pub async fn mock_interaction_response<'a>(server: &'a MockServer, content: &str) -> Mock<'a> {
+	server
+		.mock_async(|when, then| {
+			when.method(POST)
+				.path("/api/v10/interactions/100/interaction-token/callback")
+				.body_contains(content);
+			then.status(204);
+		})
+		.await
+}
+
+pub async fn mock_deferred_response(server: &MockServer) -> Mock<'_> {
+	server
+		.mock_async(|when, then| {
+			when.method(POST)
+				.path("/api/v10/interactions/100/interaction-token/callback")
+				.json_body_partial(r#"{"type": 5, "data": {"flags": 64}}"#);
+			then.status(204);
+		})
+		.await
+}
+
+pub async fn mock_acknowledged_component(server: &MockServer) -> Mock<'_> {
+	server
+		.mock_async(|when, then| {
+			when.method(POST)
+				.path("/api/v10/interactions/100/interaction-token/callback")
+				.json_body_partial(r#"{"type": 6}"#);
+			then.status(204);
+		})
+		.await
+}
+
+// Matches the edit that replaces a deferred response with the final content
+pub async fn mock_edited_response<'a>(server: &'a MockServer, content: &str) -> Mock<'a> {
+	server
+		.mock_async(|when, then| {
+			when.method(PATCH)
+				.path(format!(
+					"/api/v10/webhooks/{}/interaction-token/messages/@original",
+					APP_CONFIG.client_config.discord_app_id
+				))
+				.body_contains(content);
+			then.status(200).json_body(discord_message(
+				test_guild_config().discord_request_channel_id,
+				2
+			));
+		})
+		.await
+}
+
+pub async fn mock_follow_up<'a>(server: &'a MockServer, content: &str) -> Mock<'a> {
+	server
+		.mock_async(|when, then| {
+			when.method(POST)
+				.path(format!(
+					"/api/v10/webhooks/{}/interaction-token",
+					APP_CONFIG.client_config.discord_app_id
+				))
+				.body_contains(content);
+			then.status(200).json_body(discord_message(
+				test_guild_config().discord_request_channel_id,
+				3
+			));
+		})
+		.await
+}
+
+pub async fn mock_create_message(
+	server: &MockServer,
+	channel_id: u64,
+	message_id: u64
+) -> Mock<'_> {
+	server
+		.mock_async(|when, then| {
+			when.method(POST)
+				.path(format!("/api/v10/channels/{}/messages", channel_id));
+			then.status(200)
+				.json_body(discord_message(channel_id, message_id));
+		})
+		.await
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DiscordGatewayCall {
+	PostMessage {
+		channel_id: u64,
+		message_id: u64
+	},
+	EditMessage {
+		channel_id: u64,
+		message_id: u64
+	},
+	DeleteMessage {
+		channel_id: u64,
+		message_id: u64
+	},
+	DeleteThread {
+		thread_id: u64
+	},
+	CreateThread {
+		channel_id: u64,
+		message_id: u64,
+		thread_id: u64
+	},
+	AddRole {
+		user_id: u64,
+		role_id: u64
+	},
+	RemoveRole {
+		user_id: u64,
+		role_id: u64
+	}
+}
+
+// Records every call instead of talking to Discord, created messages and
+// threads get sequential IDs starting at 1000
+#[derive(Default)]
+pub struct RecordingDiscordGateway {
+	pub calls: Mutex<Vec<DiscordGatewayCall>>,
+	last_id: AtomicU64
+}
+
+impl RecordingDiscordGateway {
+	fn record(&self, call: DiscordGatewayCall) { self.calls.lock().unwrap().push(call); }
+
+	fn next_id(&self) -> u64 { 1000 + self.last_id.fetch_add(1, Ordering::SeqCst) }
+}
+
+#[async_trait]
+impl DiscordGateway for RecordingDiscordGateway {
+	async fn post_message(
+		&self,
+		channel_id: u64,
+		_message: &RenderedMessage,
+		_guild_config: &GuildConfig
+	) -> Result<u64, DiscordGatewayError> {
+		let message_id = self.next_id();
+		self.record(DiscordGatewayCall::PostMessage {
+			channel_id,
+			message_id
+		});
+		Ok(message_id)
+	}
+
+	async fn edit_message(
+		&self,
+		channel_id: u64,
+		message_id: u64,
+		_message: &RenderedMessage,
+		_guild_config: &GuildConfig
+	) -> Result<(), DiscordGatewayError> {
+		self.record(DiscordGatewayCall::EditMessage {
+			channel_id,
+			message_id
+		});
+		Ok(())
+	}
+
+	async fn delete_message(
+		&self,
+		channel_id: u64,
+		message_id: u64
+	) -> Result<(), DiscordGatewayError> {
+		self.record(DiscordGatewayCall::DeleteMessage {
+			channel_id,
+			message_id
+		});
+		Ok(())
+	}
+
+	async fn delete_thread(&self, thread_id: u64) -> Result<(), DiscordGatewayError> {
+		self.record(DiscordGatewayCall::DeleteThread { thread_id });
+		Ok(())
+	}
+
+	async fn create_thread(
+		&self,
+		channel_id: u64,
+		message_id: u64,
+		_name: String,
+		_audit_log_reason: String
+	) -> Result<u64, DiscordGatewayError> {
+		let thread_id = self.next_id();
+		self.record(DiscordGatewayCall::CreateThread {
+			channel_id,
+			message_id,
+			thread_id
+		});
+		Ok(thread_id)
+	}
+
+	async fn add_role(
+		&self,
+		_guild_id: u64,
+		user_id: u64,
+		role_id: u64
+	) -> Result<(), DiscordGatewayError> {
+		self.record(DiscordGatewayCall::AddRole { user_id, role_id });
+		Ok(())
+	}
+
+	async fn remove_role(
+		&self,
+		_guild_id: u64,
+		user_id: u64,
+		role_id: u64
+	) -> Result<(), DiscordGatewayError> {
+		self.record(DiscordGatewayCall::RemoveRole { user_id, role_id });
+		Ok(())
+	}
+
+	fn mention_user(&self, user_id: u64) -> String { format!("<@{}>", user_id) }
+}
@@ -0,0 +1,105 @@
+use std::{
+	fs::OpenOptions,
+	io::{self, Write}
+};
+
+use chrono::Utc;
+use log::{error, info};
+
+use crate::{
+	config::{audit_config::AUDIT_CONFIG, client_config::GuildConfig},
+	model::audit_event::{AuditEvent, AuditRecord},
+	util::{discord::log_to_discord, render::render_audit_event}
+};
+
+const AUDIT_LOG_TARGET: &str = "requestx_discord_client::audit";
+
+// Every moderation action goes through here so the log channel, the log4rs
+// JSON log and the optional JSONL file always agree
+pub async fn audit(event: AuditEvent, guild_config: &GuildConfig) {
+	let audit_record = AuditRecord {
+		timestamp: Utc::now(),
+		guild_id: guild_config.discord_guild_id,
+		event
+	};
+	match serde_json::to_string(&audit_record) {
+		Ok(audit_json) => {
+			info!(target: AUDIT_LOG_TARGET, "{}", audit_json);
+			if let Some(jsonl_path) = &AUDIT_CONFIG.jsonl_path {
+				if let Err(error) = append_jsonl(jsonl_path, &audit_json) {
+					error!("Unable to write audit event to {}: {}", jsonl_path, error);
+				}
+			}
+		}
+		Err(error) => error!(
+			"Unable to serialize audit event {:?}: {}",
+			audit_record, error
+		)
+	}
+	log_to_discord(&render_audit_event(&audit_record), guild_config).await
+}
+
+fn append_jsonl(jsonl_path: &str, audit_json: &str) -> Result<(), io::Error> {
+	let mut jsonl_file = OpenOptions::new()
+		.create(true)
+		.append(true)
+		.open(jsonl_path)?;
+	jsonl_file.write_all(format!("{}\n", audit_json).as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{env, fs};
+
+	use chrono::{TimeZone, Utc};
+	use serde_json::{json, Value};
+
+	use crate::{
+		model::{
+			audit_event::{AuditEvent, AuditRecord, AuditUser},
+			moderator::{SuggestedRating, SuggestedScore}
+		},
+		util::audit::append_jsonl
+	};
+
+	#[test]
+	fn audit_records_should_be_appended_as_json_lines() {
+		let jsonl_path = env::temp_dir().join("requestx_audit_test.jsonl");
+		let _ = fs::remove_file(&jsonl_path);
+		let audit_record = AuditRecord {
+			timestamp: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+			guild_id: 1,
+			event: AuditEvent::LevelSent {
+				moderator: AuditUser {
+					id: 555,
+					name: "somemoderator".to_string()
+				},
+				level_id: 97624039,
+				level_name: "Some Level".to_string(),
+				suggested_score: SuggestedScore::Four,
+				suggested_rating: SuggestedRating::Feature
+			}
+		};
+		let audit_json = serde_json::to_string(&audit_record).unwrap();
+
+		append_jsonl(jsonl_path.to_str().unwrap(), &audit_json).unwrap();
+		append_jsonl(jsonl_path.to_str().unwrap(), &audit_json).unwrap();
+		let jsonl = fs::read_to_string(&jsonl_path).unwrap();
+		let _ = fs::remove_file(&jsonl_path);
+
+		assert_eq!(jsonl.lines().count(), 2);
+		assert_eq!(
+			serde_json::from_str::<Value>(jsonl.lines().next().unwrap()).unwrap(),
+			json!({
+				"timestamp": "2024-01-01T00:00:00Z",
+				"guild_id": 1,
+				"event": "LevelSent",
+				"moderator": { "id": 555, "name": "somemoderator" },
+				"level_id": 97624039,
+				"level_name": "Some Level",
+				"suggested_score": "Four",
+				"suggested_rating": "Feature"
+			})
+		);
+	}
+}
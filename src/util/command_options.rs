@@ -0,0 +1,309 @@
+use std::str::FromStr;
+
+use serenity::all::{
+	CommandDataOption, CommandDataOptionValue, CommandOptionType, CreateCommandOption, UserId
+};
+
+use crate::model::error::command_option_error::CommandOptionError;
+
+// Options are looked up by name so reordering them in the command definition
+// cannot feed one option's value into another
+fn get_option_value<'a>(
+	options: &'a [CommandDataOption],
+	option_name: &str
+) -> Result<&'a CommandDataOptionValue, CommandOptionError> {
+	options
+		.iter()
+		.find(|option| option.name == option_name)
+		.map(|option| &option.value)
+		.ok_or(CommandOptionError::MissingOption(option_name.to_string()))
+}
+
+pub fn get_unsigned_integer_option(
+	options: &[CommandDataOption],
+	option_name: &str
+) -> Result<u64, CommandOptionError> {
+	u64::try_from(get_integer_option(options, option_name)?)
+		.map_err(|_| CommandOptionError::InvalidOption(option_name.to_string()))
+}
+
+pub fn get_integer_option(
+	options: &[CommandDataOption],
+	option_name: &str
+) -> Result<i64, CommandOptionError> {
+	get_option_value(options, option_name)?
+		.as_i64()
+		.ok_or(CommandOptionError::InvalidOption(option_name.to_string()))
+}
+
+pub fn get_string_option<'a>(
+	options: &'a [CommandDataOption],
+	option_name: &str
+) -> Result<&'a str, CommandOptionError> {
+	get_option_value(options, option_name)?
+		.as_str()
+		.ok_or(CommandOptionError::InvalidOption(option_name.to_string()))
+}
+
+pub fn get_boolean_option(
+	options: &[CommandDataOption],
+	option_name: &str
+) -> Result<bool, CommandOptionError> {
+	get_option_value(options, option_name)?
+		.as_bool()
+		.ok_or(CommandOptionError::InvalidOption(option_name.to_string()))
+}
+
+pub fn get_user_option(
+	options: &[CommandDataOption],
+	option_name: &str
+) -> Result<UserId, CommandOptionError> {
+	get_option_value(options, option_name)?
+		.as_user_id()
+		.ok_or(CommandOptionError::InvalidOption(option_name.to_string()))
+}
+
+pub fn get_parsed_option<T: FromStr>(
+	options: &[CommandDataOption],
+	option_name: &str
+) -> Result<T, CommandOptionError> {
+	T::from_str(get_string_option(options, option_name)?)
+		.map_err(|_| CommandOptionError::InvalidOption(option_name.to_string()))
+}
+
+type OptionParser<T> = Box<dyn Fn(&[CommandDataOption]) -> Result<T, CommandOptionError>>;
+
+// A command option's registration paired with the parser for its value, so
+// both are always built from the same name
+pub struct CommandOption<T> {
+	name: &'static str,
+	option: CreateCommandOption,
+	parse: OptionParser<T>
+}
+
+impl<T: 'static> CommandOption<T> {
+	fn new(
+		kind: CommandOptionType,
+		name: &'static str,
+		description: &str,
+		parse: fn(&[CommandDataOption], &str) -> Result<T, CommandOptionError>
+	) -> Self {
+		CommandOption {
+			name,
+			option: CreateCommandOption::new(kind, name, description).required(true),
+			parse: Box::new(move |options| parse(options, name))
+		}
+	}
+
+	pub fn autocomplete(mut self) -> Self {
+		self.option = self.option.set_autocomplete(true);
+		self
+	}
+
+	pub fn min_int_value(mut self, min_int_value: u64) -> Self {
+		self.option = self.option.min_int_value(min_int_value);
+		self
+	}
+
+	pub fn optional(self) -> CommandOption<Option<T>> {
+		let name = self.name;
+		let parse = self.parse;
+		CommandOption {
+			name,
+			option: self.option.required(false),
+			parse: Box::new(move |options| {
+				if options.iter().any(|option| option.name == name) {
+					parse(options).map(Some)
+				} else {
+					Ok(None)
+				}
+			})
+		}
+	}
+
+	pub fn create_option(self) -> CreateCommandOption { self.option }
+
+	pub fn parse(&self, options: &[CommandDataOption]) -> Result<T, CommandOptionError> {
+		(self.parse)(options)
+	}
+}
+
+impl CommandOption<u64> {
+	pub fn unsigned_integer(name: &'static str, description: &str) -> Self {
+		CommandOption::new(
+			CommandOptionType::Integer,
+			name,
+			description,
+			get_unsigned_integer_option
+		)
+	}
+}
+
+impl CommandOption<String> {
+	pub fn string(name: &'static str, description: &str) -> Self {
+		CommandOption::new(
+			CommandOptionType::String,
+			name,
+			description,
+			|options, option_name| get_string_option(options, option_name).map(str::to_string)
+		)
+	}
+}
+
+impl CommandOption<bool> {
+	pub fn boolean(name: &'static str, description: &str) -> Self {
+		CommandOption::new(
+			CommandOptionType::Boolean,
+			name,
+			description,
+			get_boolean_option
+		)
+	}
+}
+
+impl CommandOption<UserId> {
+	pub fn user(name: &'static str, description: &str) -> Self {
+		CommandOption::new(CommandOptionType::User, name, description, get_user_option)
+	}
+}
+
+impl<T: FromStr + 'static> CommandOption<T> {
+	// Choices are `(label, value)` pairs where the value parses into `T`
+	pub fn choice(name: &'static str, description: &str, choices: &[(&str, &str)]) -> Self {
+		let mut command_option = CommandOption::new(
+			CommandOptionType::String,
+			name,
+			description,
+			get_parsed_option
+		);
+		for (label, value) in choices {
+			command_option.option = command_option.option.add_string_choice(*label, *value);
+		}
+		command_option
+	}
+}
+
+// Declares a struct of command arguments together with the options to
+// register, so a command's definition and parsing cannot drift apart
+macro_rules! command_args {
+	(
+		pub struct $name:ident {
+			$($field:ident: $field_type:ty = $option:expr),* $(,)?
+		}
+	) => {
+		pub struct $name {
+			$(pub $field: $field_type),*
+		}
+
+		impl $name {
+			pub fn create_options() -> Vec<serenity::all::CreateCommandOption> {
+				vec![$({
+					let option: $crate::util::command_options::CommandOption<$field_type> = $option;
+					option.create_option()
+				}),*]
+			}
+
+			pub fn parse(
+				options: &[serenity::all::CommandDataOption]
+			) -> Result<Self, $crate::model::error::command_option_error::CommandOptionError> {
+				Ok($name {
+					$($field: {
+						let option: $crate::util::command_options::CommandOption<$field_type> =
+							$option;
+						option.parse(options)?
+					}),*
+				})
+			}
+		}
+	};
+}
+
+pub(crate) use command_args;
+
+#[cfg(test)]
+mod tests {
+	use serde_json::json;
+	use serenity::all::CommandDataOption;
+
+	use crate::{
+		model::{error::command_option_error::CommandOptionError, request_score::RequestRating},
+		util::command_options::{get_integer_option, get_parsed_option, CommandOption}
+	};
+
+	command_args! {
+		pub struct TestArgs {
+			level_id: u64 = CommandOption::unsigned_integer("level-id", "A level ID"),
+			request_rating: Option<RequestRating> = CommandOption::choice(
+				"request-rating",
+				"A request rating",
+				&[("Hard, 4 Stars/Moons", "Four")]
+			)
+			.optional(),
+			notify: Option<bool> = CommandOption::boolean("notify", "Notify").optional()
+		}
+	}
+
+	fn test_options() -> Vec<CommandDataOption> {
+		serde_json::from_value(json!([
+			{"name": "request-rating", "type": 3, "value": "Four"},
+			{"name": "level-id", "type": 4, "value": 97624039}
+		]))
+		.unwrap()
+	}
+
+	#[test]
+	fn options_should_be_found_by_name_regardless_of_order() {
+		let options = test_options();
+
+		assert_eq!(get_integer_option(&options, "level-id"), Ok(97624039));
+		assert_eq!(
+			get_parsed_option::<RequestRating>(&options, "request-rating"),
+			Ok(RequestRating::Four)
+		);
+	}
+
+	#[test]
+	fn missing_and_invalid_options_should_error() {
+		let options = test_options();
+
+		assert_eq!(
+			get_integer_option(&options, "notify"),
+			Err(CommandOptionError::MissingOption("notify".to_string()))
+		);
+		assert_eq!(
+			get_integer_option(&options, "request-rating"),
+			Err(CommandOptionError::InvalidOption(
+				"request-rating".to_string()
+			))
+		);
+	}
+
+	#[test]
+	fn command_args_should_register_and_parse_the_same_options() {
+		let registered_options = TestArgs::create_options()
+			.into_iter()
+			.map(|option| serde_json::to_value(option).unwrap())
+			.collect::<Vec<_>>();
+		let args = TestArgs::parse(&test_options()).unwrap();
+
+		assert_eq!(registered_options[0]["name"], "level-id");
+		assert_eq!(registered_options[0]["required"], true);
+		assert_eq!(registered_options[2]["required"], false);
+		assert_eq!(args.level_id, 97624039);
+		assert_eq!(args.request_rating, Some(RequestRating::Four));
+		assert_eq!(args.notify, None);
+	}
+
+	#[test]
+	fn command_args_should_reject_negative_unsigned_options() {
+		let options: Vec<CommandDataOption> = serde_json::from_value(json!([
+			{"name": "level-id", "type": 4, "value": -1}
+		]))
+		.unwrap();
+
+		assert_eq!(
+			TestArgs::parse(&options).err(),
+			Some(CommandOptionError::InvalidOption("level-id".to_string()))
+		);
+	}
+}
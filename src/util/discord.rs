@@ -1,68 +1,138 @@
-use log::error;
+use std::sync::Arc;
+
+use log::{error, warn};
 use serenity::{
 	all::{
-		ChannelId, CommandInteraction, Context, CreateInteractionResponse,
-		CreateInteractionResponseMessage, CreateThread
+		CommandInteraction, ComponentInteraction, CreateInteractionResponse,
+		CreateInteractionResponseFollowup, CreateInteractionResponseMessage,
+		EditInteractionResponse, Http, InteractionId, ModalInteraction
 	},
-	Error
+	builder::Builder
 };
-use tokio::{sync::mpsc, task};
 
 use crate::{
-	config::client_config::CLIENT_CONFIG, model::requestx_api::level_request_data::LevelRequestData
+	config::client_config::GuildConfig,
+	util::{discord_log_sink::DISCORD_LOG_SINK, render::RenderedMessage}
 };
 
-pub async fn create_thread(
-	ctx: &Context,
-	command: &CommandInteraction,
-	message_id: u64,
-	level: &LevelRequestData
-) -> Result<u64, Error> {
-	match ChannelId::new(1193493680594616411)
-		.create_thread_from_message(
-			&ctx.http,
-			message_id,
-			CreateThread::new(format!("\"{}\" ({})", level.level_name, level.level_id))
-				.audit_log_reason(&*format!(
-					"Created via {} command by: {} {}",
-					command.data.name, command.user.name, command.user.id,
-				))
-				.invitable(false)
-		)
-		.await
-	{
-		Ok(thread_channel_id) => Ok(thread_channel_id.id.get()),
-		Err(error) => Err(error)
+pub async fn invoke_ephermal(content: &str, http: &Arc<Http>, command: &CommandInteraction) {
+	let data = CreateInteractionResponseMessage::new()
+		.ephemeral(true)
+		.content(content);
+	let builder = CreateInteractionResponse::Message(data);
+	if let Err(err) = command.create_response(http, builder).await {
+		error!("Cannot respond to slash command: {err}");
 	}
 }
 
-pub async fn invoke_ephermal(content: &str, ctx: &Context, command: &CommandInteraction) {
+pub async fn invoke_component_ephermal(
+	content: &str,
+	http: &Arc<Http>,
+	component: &ComponentInteraction
+) {
 	let data = CreateInteractionResponseMessage::new()
 		.ephemeral(true)
 		.content(content);
 	let builder = CreateInteractionResponse::Message(data);
-	if let Err(err) = command.create_response(&ctx.http, builder).await {
-		error!("Cannot respond to slash command: {err}");
+	if let Err(err) = component.create_response(http, builder).await {
+		error!("Cannot respond to component: {err}");
+	}
+}
+
+pub async fn invoke_modal_ephermal(content: &str, http: &Arc<Http>, modal: &ModalInteraction) {
+	let data = CreateInteractionResponseMessage::new()
+		.ephemeral(true)
+		.content(content);
+	let builder = CreateInteractionResponse::Message(data);
+	if let Err(err) = modal.create_response(http, builder).await {
+		error!("Cannot respond to modal: {err}");
 	}
 }
 
-async fn discord_log(mut rx: mpsc::Receiver<(String, Context)>) {
-	while let Some(data) = rx.recv().await {
-		if let Err(logger_error) = ChannelId::new(CLIENT_CONFIG.discord_log_channel_id)
-			.say(&data.1.http, &data.0)
+// Acknowledges the interaction straight away with an ephemeral "thinking..."
+// response so slow workflows stay within Discord's 3 second window, the result
+// is shown later by editing the original response
+pub struct DeferredResponse {
+	http: Arc<Http>,
+	token: String
+}
+
+impl DeferredResponse {
+	pub async fn defer_command(http: &Arc<Http>, command: &CommandInteraction) -> Self {
+		Self::defer(http, command.id, &command.token, Self::thinking()).await
+	}
+
+	pub async fn defer_modal(http: &Arc<Http>, modal: &ModalInteraction) -> Self {
+		Self::defer(http, modal.id, &modal.token, Self::thinking()).await
+	}
+
+	// Components keep their message instead of showing "thinking...", editing
+	// the response then updates the message the component is attached to
+	pub async fn defer_component_update(
+		http: &Arc<Http>,
+		component: &ComponentInteraction
+	) -> Self {
+		Self::defer(
+			http,
+			component.id,
+			&component.token,
+			CreateInteractionResponse::Acknowledge
+		)
+		.await
+	}
+
+	fn thinking() -> CreateInteractionResponse {
+		CreateInteractionResponse::Defer(CreateInteractionResponseMessage::new().ephemeral(true))
+	}
+
+	async fn defer(
+		http: &Arc<Http>,
+		interaction_id: InteractionId,
+		token: &str,
+		builder: CreateInteractionResponse
+	) -> Self {
+		if let Err(err) = builder.execute(http, (interaction_id, token)).await {
+			error!("Cannot defer interaction response: {err}");
+		}
+		DeferredResponse {
+			http: http.clone(),
+			token: token.to_string()
+		}
+	}
+
+	pub async fn edit(&self, content: &str) {
+		self.edit_with(EditInteractionResponse::new().content(content))
 			.await
-		{
-			error!(
-				"Unable to log event {} to Discord: {}",
-				data.0, logger_error
-			);
+	}
+
+	pub async fn edit_with(&self, builder: EditInteractionResponse) {
+		if let Err(err) = builder.execute(&self.http, &self.token).await {
+			error!("Cannot edit interaction response: {err}");
+		}
+	}
+
+	// Adds another ephemeral message below the original response, used when a
+	// later step of a workflow has its own outcome to report
+	pub async fn follow_up(&self, content: &str) {
+		let builder = CreateInteractionResponseFollowup::new()
+			.ephemeral(true)
+			.content(content);
+		if let Err(err) = builder.execute(&self.http, (None, &self.token)).await {
+			error!("Cannot send interaction follow-up: {err}");
 		}
 	}
 }
 
-pub async fn log_to_discord(log_text: String, ctx: Context) {
-	let (tx, rx) = mpsc::channel::<(String, Context)>(32);
-	task::spawn(discord_log(rx));
-	tx.send((log_text, ctx)).await.unwrap();
-	drop(tx);
+pub async fn log_to_discord(log_message: &RenderedMessage, guild_config: &GuildConfig) {
+	match DISCORD_LOG_SINK.get() {
+		Some(log_sink) => {
+			log_sink
+				.log(
+					guild_config.discord_log_channel_id,
+					log_message.to_log_entry(guild_config)
+				)
+				.await
+		}
+		None => warn!("Discord log sink is not running, dropping log message")
+	}
 }
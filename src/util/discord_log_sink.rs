@@ -0,0 +1,292 @@
+use std::{
+	collections::BTreeMap,
+	sync::{Arc, Mutex, OnceLock},
+	time::Duration
+};
+
+use log::{error, warn};
+use serenity::all::{ChannelId, CreateAttachment, CreateEmbed, CreateMessage, Http, HttpError};
+use tokio::{
+	sync::mpsc,
+	task::JoinHandle,
+	time::{sleep, timeout_at, Instant}
+};
+
+const LOG_QUEUE_CAPACITY: usize = 256;
+const LOG_BATCH_WINDOW: Duration = Duration::from_millis(500);
+const MAX_MESSAGE_LENGTH: usize = 2000;
+// Audit embeds stay well under 1200 characters, so this keeps a message within
+// Discord's 6000 character total for embeds
+const MAX_EMBEDS_PER_MESSAGE: usize = 5;
+const MAX_SEND_ATTEMPTS: u32 = 3;
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+
+pub static DISCORD_LOG_SINK: OnceLock<DiscordLogSink> = OnceLock::new();
+
+pub enum LogEntry {
+	Text(String),
+	Embed(Box<CreateEmbed>)
+}
+
+enum LogSinkMessage {
+	Entry {
+		channel_id: u64,
+		log_entry: LogEntry
+	},
+	Shutdown
+}
+
+#[derive(Debug, PartialEq)]
+enum LogMessage {
+	Text(String),
+	Attachment(String),
+	Embeds(Vec<CreateEmbed>)
+}
+
+// A single task owns every write to the log channels, entries logged in a
+// burst are combined so a busy guild does not hit Discord's rate limits
+pub struct DiscordLogSink {
+	sender: mpsc::Sender<LogSinkMessage>,
+	task: Mutex<Option<JoinHandle<()>>>
+}
+
+impl DiscordLogSink {
+	pub fn start(http: Arc<Http>) -> Self {
+		let (sender, receiver) = mpsc::channel(LOG_QUEUE_CAPACITY);
+		DiscordLogSink {
+			sender,
+			task: Mutex::new(Some(tokio::spawn(run_log_sink(http, receiver))))
+		}
+	}
+
+	pub async fn log(&self, channel_id: u64, log_entry: LogEntry) {
+		if self
+			.sender
+			.send(LogSinkMessage::Entry {
+				channel_id,
+				log_entry
+			})
+			.await
+			.is_err()
+		{
+			error!("Discord log sink has stopped, dropping log entry");
+		}
+	}
+
+	// Sends everything queued so far before returning, entries logged after
+	// this are dropped
+	pub async fn shutdown(&self) {
+		let task = self.task.lock().unwrap().take();
+		if let Some(task) = task {
+			let _ = self.sender.send(LogSinkMessage::Shutdown).await;
+			if let Err(error) = task.await {
+				error!("Discord log sink failed to shut down: {}", error);
+			}
+		}
+	}
+}
+
+async fn run_log_sink(http: Arc<Http>, mut receiver: mpsc::Receiver<LogSinkMessage>) {
+	let mut running = true;
+	while running {
+		let mut pending_entries = BTreeMap::new();
+		running = queue_entry(&mut pending_entries, receiver.recv().await);
+
+		let batch_deadline = Instant::now() + LOG_BATCH_WINDOW;
+		while running {
+			match timeout_at(batch_deadline, receiver.recv()).await {
+				Ok(message) => running = queue_entry(&mut pending_entries, message),
+				Err(_) => break
+			}
+		}
+
+		if !running {
+			receiver.close();
+			while let Ok(message) = receiver.try_recv() {
+				queue_entry(&mut pending_entries, Some(message));
+			}
+		}
+
+		for (channel_id, log_entries) in pending_entries {
+			for log_message in batch_log_entries(log_entries) {
+				send_log_message(&http, channel_id, log_message).await;
+			}
+		}
+	}
+}
+
+// Returns false once the sink has been asked to stop
+fn queue_entry(
+	pending_entries: &mut BTreeMap<u64, Vec<LogEntry>>,
+	message: Option<LogSinkMessage>
+) -> bool {
+	match message {
+		Some(LogSinkMessage::Entry {
+			channel_id,
+			log_entry
+		}) => {
+			pending_entries
+				.entry(channel_id)
+				.or_default()
+				.push(log_entry);
+			true
+		}
+		Some(LogSinkMessage::Shutdown) | None => false
+	}
+}
+
+// Entries are never split so code blocks stay intact, a text entry that does
+// not fit in a message on its own is sent as a file instead
+fn batch_log_entries(log_entries: Vec<LogEntry>) -> Vec<LogMessage> {
+	let mut log_messages = Vec::new();
+	let mut content = String::new();
+	let mut embeds = Vec::new();
+	for log_entry in log_entries {
+		match log_entry {
+			LogEntry::Text(log_text) => {
+				if !embeds.is_empty() {
+					log_messages.push(LogMessage::Embeds(std::mem::take(&mut embeds)));
+				}
+				let text_length = log_text.chars().count();
+				if !content.is_empty()
+					&& content.chars().count() + 1 + text_length > MAX_MESSAGE_LENGTH
+				{
+					log_messages.push(LogMessage::Text(std::mem::take(&mut content)));
+				}
+				if text_length > MAX_MESSAGE_LENGTH {
+					log_messages.push(LogMessage::Attachment(log_text));
+				} else {
+					if !content.is_empty() {
+						content.push('\n');
+					}
+					content.push_str(&log_text);
+				}
+			}
+			LogEntry::Embed(embed) => {
+				if !content.is_empty() {
+					log_messages.push(LogMessage::Text(std::mem::take(&mut content)));
+				}
+				if embeds.len() == MAX_EMBEDS_PER_MESSAGE {
+					log_messages.push(LogMessage::Embeds(std::mem::take(&mut embeds)));
+				}
+				embeds.push(*embed);
+			}
+		}
+	}
+	if !content.is_empty() {
+		log_messages.push(LogMessage::Text(content));
+	}
+	if !embeds.is_empty() {
+		log_messages.push(LogMessage::Embeds(embeds));
+	}
+	log_messages
+}
+
+async fn send_log_message(http: &Arc<Http>, channel_id: u64, log_message: LogMessage) {
+	for attempt in 1..=MAX_SEND_ATTEMPTS {
+		let builder = match &log_message {
+			LogMessage::Text(content) => CreateMessage::new().content(content),
+			LogMessage::Attachment(content) => CreateMessage::new()
+				.content("Log entry is too long, see the attached file")
+				.add_file(CreateAttachment::bytes(content.as_bytes(), "log.txt")),
+			LogMessage::Embeds(embeds) => CreateMessage::new().embeds(embeds.clone())
+		};
+		match ChannelId::new(channel_id).send_message(http, builder).await {
+			Ok(_) => return,
+			Err(error) if is_rate_limited(&error) && attempt < MAX_SEND_ATTEMPTS => {
+				warn!("Rate limited while logging to Discord, retrying");
+				sleep(RETRY_BASE_DELAY * 2u32.pow(attempt - 1)).await;
+			}
+			Err(error) => {
+				error!("Unable to log {:?} to Discord: {}", log_message, error);
+				return;
+			}
+		}
+	}
+}
+
+fn is_rate_limited(error: &serenity::Error) -> bool {
+	matches!(
+		error,
+		serenity::Error::Http(HttpError::UnsuccessfulRequest(response))
+			if response.status_code.as_u16() == 429
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use httpmock::Method::POST;
+	use serenity::all::CreateEmbed;
+
+	use crate::{
+		test_harness::{discord_message, init_mock_server, test_http},
+		util::discord_log_sink::{
+			batch_log_entries, DiscordLogSink, LogEntry, LogMessage, MAX_EMBEDS_PER_MESSAGE,
+			MAX_MESSAGE_LENGTH
+		}
+	};
+
+	#[test]
+	fn log_entries_should_be_batched_within_the_message_limit() {
+		let long_entry = "a".repeat(MAX_MESSAGE_LENGTH - 10);
+		let oversized_entry = "b".repeat(MAX_MESSAGE_LENGTH + 1);
+
+		assert_eq!(
+			batch_log_entries(vec![
+				LogEntry::Text("first".to_string()),
+				LogEntry::Text("second".to_string()),
+				LogEntry::Text(long_entry.clone()),
+				LogEntry::Text(oversized_entry.clone()),
+				LogEntry::Text("third".to_string())
+			]),
+			vec![
+				LogMessage::Text("first\nsecond".to_string()),
+				LogMessage::Text(long_entry),
+				LogMessage::Attachment(oversized_entry),
+				LogMessage::Text("third".to_string())
+			]
+		);
+	}
+
+	#[test]
+	fn embeds_should_be_grouped_per_message() {
+		let embed = CreateEmbed::new().title("Review posted");
+		let log_entries = (0..MAX_EMBEDS_PER_MESSAGE + 1)
+			.map(|_| LogEntry::Embed(Box::new(embed.clone())))
+			.chain([LogEntry::Text("entry".to_string())])
+			.collect();
+
+		assert_eq!(
+			batch_log_entries(log_entries),
+			vec![
+				LogMessage::Embeds(vec![embed.clone(); MAX_EMBEDS_PER_MESSAGE]),
+				LogMessage::Embeds(vec![embed]),
+				LogMessage::Text("entry".to_string())
+			]
+		);
+	}
+
+	#[tokio::test]
+	async fn shutdown_should_send_queued_entries_in_one_message() {
+		let server = init_mock_server().await;
+		let log_message_mock = server
+			.mock_async(|when, then| {
+				when.method(POST)
+					.path("/api/v10/channels/50/messages")
+					.body_contains(r"first entry\nsecond entry");
+				then.status(200).json_body(discord_message(50, 1));
+			})
+			.await;
+		let log_sink = DiscordLogSink::start(test_http(&server));
+
+		log_sink
+			.log(50, LogEntry::Text("first entry".to_string()))
+			.await;
+		log_sink
+			.log(50, LogEntry::Text("second entry".to_string()))
+			.await;
+		log_sink.shutdown().await;
+
+		log_message_mock.assert_async().await;
+	}
+}
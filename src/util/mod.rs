@@ -1 +1,6 @@
+pub mod audit;
+pub mod command_options;
 pub mod discord;
+pub mod discord_log_sink;
+pub mod permission;
+pub mod render;
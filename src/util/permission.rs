@@ -0,0 +1,133 @@
+use std::sync::Arc;
+
+use log::warn;
+use serenity::all::{
+	CommandInteraction, ComponentInteraction, Http, Member, ModalInteraction, User
+};
+
+use crate::{
+	config::client_config::GuildConfig,
+	model::{audit_event::AuditEvent, permission::Permission},
+	util::{
+		audit::audit,
+		discord::{invoke_component_ephermal, invoke_ephermal, invoke_modal_ephermal}
+	}
+};
+
+pub fn get_required_permission(command_name: &str) -> Permission {
+	match command_name {
+		"review" => Permission::Reviewer,
+		"send-level" | "queue" => Permission::Moderator,
+		"add-reviewer" | "remove-reviewer" => Permission::Admin,
+		_ => Permission::Requester
+	}
+}
+
+pub fn get_permission(
+	user: &User,
+	member: Option<&Member>,
+	guild_config: &GuildConfig
+) -> Option<Permission> {
+	let role_ids: Vec<u64> = member
+		.map(|member| member.roles.iter().map(|role_id| role_id.get()).collect())
+		.unwrap_or_default();
+
+	Permission::resolve(guild_config, user.id.get(), &role_ids)
+}
+
+pub async fn authorize(
+	http: &Arc<Http>,
+	command: &CommandInteraction,
+	guild_config: &GuildConfig,
+	required_permission: Permission
+) -> bool {
+	if is_authorized(
+		&command.user,
+		command.member.as_deref(),
+		&command.data.name,
+		guild_config,
+		required_permission
+	)
+	.await
+	{
+		true
+	} else {
+		invoke_ephermal("Forbidden", http, command).await;
+		false
+	}
+}
+
+pub async fn authorize_component(
+	http: &Arc<Http>,
+	component: &ComponentInteraction,
+	guild_config: &GuildConfig,
+	required_permission: Permission
+) -> bool {
+	if is_authorized(
+		&component.user,
+		component.member.as_ref(),
+		&component.data.custom_id,
+		guild_config,
+		required_permission
+	)
+	.await
+	{
+		true
+	} else {
+		invoke_component_ephermal("Forbidden", http, component).await;
+		false
+	}
+}
+
+// Modals are submitted separately from the command that opened them, so the
+// submitter is checked again in case their roles changed in between
+pub async fn authorize_modal(
+	http: &Arc<Http>,
+	modal: &ModalInteraction,
+	guild_config: &GuildConfig,
+	required_permission: Permission
+) -> bool {
+	if is_authorized(
+		&modal.user,
+		modal.member.as_ref(),
+		&modal.data.custom_id,
+		guild_config,
+		required_permission
+	)
+	.await
+	{
+		true
+	} else {
+		invoke_modal_ephermal("Forbidden", http, modal).await;
+		false
+	}
+}
+
+async fn is_authorized(
+	user: &User,
+	member: Option<&Member>,
+	action: &str,
+	guild_config: &GuildConfig,
+	required_permission: Permission
+) -> bool {
+	let permission = get_permission(user, member, guild_config);
+	if permission.is_some_and(|permission| permission >= required_permission) {
+		return true;
+	}
+
+	warn!(
+		"{} ({}) was denied {} which requires {}",
+		user.name, user.id, action, required_permission
+	);
+
+	audit(
+		AuditEvent::PermissionDenied {
+			user: user.into(),
+			action: action.to_string(),
+			required_permission
+		},
+		guild_config
+	)
+	.await;
+	false
+}
@@ -0,0 +1,551 @@
+use serenity::all::{
+	ChannelId, Colour, CreateActionRow, CreateEmbed, CreateEmbedFooter, CreateMessage, EditMessage,
+	GuildId, Mentionable, MessageBuilder, MessageId, UserId
+};
+
+use crate::{
+	config::client_config::GuildConfig,
+	model::{
+		audit_event::{AuditEvent, AuditRecord, AuditUser},
+		moderator::{Moderator, SuggestedScore},
+		request_score::{LevelLength, RequestRating},
+		requestx_api::level_request_data::LevelRequestData
+	},
+	util::discord_log_sink::LogEntry
+};
+
+// Discord rejects embed field values longer than this
+const MAX_FIELD_LENGTH: usize = 1024;
+
+pub struct RenderedMessage {
+	text: String,
+	embed: CreateEmbed,
+	mention: Option<String>,
+	components: Vec<CreateActionRow>
+}
+
+impl RenderedMessage {
+	pub fn with_components(mut self, components: Vec<CreateActionRow>) -> Self {
+		self.components = components;
+		self
+	}
+
+	pub fn to_create_message(&self, guild_config: &GuildConfig) -> CreateMessage {
+		let message = if guild_config.discord_plain_text_messages {
+			CreateMessage::new().content(&self.text)
+		} else {
+			let message = CreateMessage::new().embed(self.embed.clone());
+			match &self.mention {
+				Some(mention) => message.content(mention),
+				None => message
+			}
+		};
+		if self.components.is_empty() {
+			message
+		} else {
+			message.components(self.components.clone())
+		}
+	}
+
+	pub fn to_edit_message(&self, guild_config: &GuildConfig) -> EditMessage {
+		if guild_config.discord_plain_text_messages {
+			EditMessage::new().content(&self.text)
+		} else {
+			EditMessage::new()
+				.content(self.mention.clone().unwrap_or_default())
+				.embed(self.embed.clone())
+		}
+	}
+
+	pub fn to_log_entry(&self, guild_config: &GuildConfig) -> LogEntry {
+		if guild_config.discord_plain_text_messages {
+			LogEntry::Text(self.text.clone())
+		} else {
+			LogEntry::Embed(Box::new(self.embed.clone()))
+		}
+	}
+}
+
+pub fn render_level_request(level_request: &LevelRequestData) -> RenderedMessage {
+	let video_link = normalize_video_link(&level_request.youtube_video_link);
+	let mut text = MessageBuilder::new();
+	text.push_line(format!(
+		"\"{}\" by {}",
+		&level_request.level_name, &level_request.level_author
+	))
+	.push_line(format!("{}", &level_request.level_id))
+	.push_line(format!("Requested {}", &level_request.request_score));
+	if level_request.has_requested_feedback {
+		text.push_line("Feedback has been requested!");
+	}
+	text.push_line(&video_link);
+
+	let embed = level_embed(level_request)
+		.url(&video_link)
+		.field("Level ID", level_request.level_id.to_string(), true)
+		.field("Requested", level_request.request_score.to_string(), true)
+		.field("Length", level_request.level_length.to_string(), true)
+		.field(
+			"Feedback",
+			if level_request.has_requested_feedback {
+				"Requested"
+			} else {
+				"Not requested"
+			},
+			true
+		)
+		.field(
+			"Requested by",
+			UserId::new(level_request.discord_id).mention().to_string(),
+			true
+		)
+		.field("Video", &video_link, false);
+
+	RenderedMessage {
+		text: text.build(),
+		embed,
+		mention: None,
+		components: Vec::new()
+	}
+}
+
+pub fn render_level_review(
+	level_request: &LevelRequestData,
+	reviewer_mention: &str,
+	review_contents: &str
+) -> RenderedMessage {
+	let mention = requester_mention(level_request);
+
+	let mut text = MessageBuilder::new();
+	text.push_bold_line(format!("Review by {}", reviewer_mention))
+		.push_line("");
+	for review_line in review_contents.lines() {
+		text.push_quote_line_safe(review_line);
+	}
+	if let Some(mention) = &mention {
+		text.push_line("");
+		text.push_line(mention);
+	}
+
+	let embed = level_embed(level_request)
+		.description(review_contents)
+		.field("Reviewer", reviewer_mention, true);
+
+	RenderedMessage {
+		text: text.build(),
+		embed,
+		mention,
+		components: Vec::new()
+	}
+}
+
+// The link check accepts links without a scheme, but Discord rejects embed
+// URLs that are not absolute
+fn normalize_video_link(video_link: &str) -> String {
+	if video_link.starts_with("https://") || video_link.starts_with("http://") {
+		video_link.to_string()
+	} else if video_link.starts_with("//") {
+		format!("https:{}", video_link)
+	} else {
+		format!("https://{}", video_link)
+	}
+}
+
+pub fn render_send_level(
+	level_request: &LevelRequestData,
+	send_level_request: &Moderator
+) -> RenderedMessage {
+	let has_been_sent = send_level_request.suggested_score != SuggestedScore::NoRate;
+	let verdict = format!(
+		"{}, {} {}!",
+		send_level_request.suggested_rating,
+		send_level_request.suggested_score,
+		get_reward_name(level_request.level_length)
+	);
+	let mention = requester_mention(level_request);
+
+	let mut text = MessageBuilder::new();
+	text.push(format!(
+		"\"{}\" ({}) ",
+		level_request.level_name, level_request.level_id
+	));
+	if has_been_sent {
+		text.push_bold("has ");
+		text.push("been sent for ");
+		text.push_bold(&verdict);
+	} else {
+		text.push_bold("has not ");
+		text.push("been sent...");
+	}
+	if let Some(mention) = &mention {
+		text.push_line("");
+		text.push_line(mention);
+	}
+
+	let embed = if has_been_sent {
+		level_embed(level_request)
+			.description(format!("Has been sent for **{}**", verdict))
+			.colour(Colour::DARK_GREEN)
+	} else {
+		level_embed(level_request)
+			.description("Has not been sent...")
+			.colour(Colour::LIGHT_GREY)
+	};
+
+	RenderedMessage {
+		text: text.build(),
+		embed,
+		mention,
+		components: Vec::new()
+	}
+}
+
+pub fn render_request_status(
+	level_request: &LevelRequestData,
+	review_count: Option<usize>,
+	guild_config: &GuildConfig
+) -> String {
+	let status = match (
+		level_request.suggested_score,
+		level_request.suggested_rating
+	) {
+		(Some(SuggestedScore::NoRate), _) => "Not sent".to_string(),
+		(Some(suggested_score), Some(suggested_rating)) => format!(
+			"Sent for {}, {} {}",
+			suggested_rating,
+			suggested_score,
+			get_reward_name(level_request.level_length)
+		),
+		_ => "Waiting for a moderator".to_string()
+	};
+	let request_channel_id =
+		ChannelId::new(guild_config.get_level_request_channel_id(level_request));
+
+	let mut text = MessageBuilder::new();
+	text.push_bold_line_safe(format!(
+		"\"{}\" by {} ({})",
+		level_request.level_name, level_request.level_author, level_request.level_id
+	))
+	.push_line(format!("Status: {}", status))
+	.push_line(format!(
+		"Reviews: {}",
+		review_count.map_or("Unavailable".to_string(), |review_count| review_count
+			.to_string())
+	));
+	match level_request.discord_message_id {
+		Some(message_id) => text.push_line(format!(
+			"Request message: {}",
+			MessageId::new(message_id).link(
+				request_channel_id,
+				Some(GuildId::new(guild_config.discord_guild_id))
+			)
+		)),
+		None => text.push_line("Request message: Not posted")
+	};
+	match level_request.discord_thread_id {
+		Some(thread_id) => {
+			text.push_line(format!("Thread: {}", ChannelId::new(thread_id).mention()))
+		}
+		None => text.push_line("Thread: Not opened yet")
+	};
+	text.build()
+}
+
+pub fn render_audit_event(audit_record: &AuditRecord) -> RenderedMessage {
+	let (title, colour, fields) = match &audit_record.event {
+		AuditEvent::LevelRequested {
+			level_id,
+			level_name,
+			request_score,
+			level_length,
+			has_requested_feedback,
+			..
+		} => (
+			"Level requested",
+			get_request_rating_colour(*request_score),
+			vec![
+				("Level", format!("\"{}\" ({})", level_name, level_id)),
+				("Requested", request_score.to_string()),
+				("Length", level_length.to_string()),
+				("Feedback", has_requested_feedback.to_string()),
+			]
+		),
+		AuditEvent::LevelRequestEdited {
+			level_id,
+			level_name,
+			request_score,
+			has_requested_feedback,
+			notify,
+			..
+		} => (
+			"Level request edited",
+			get_request_rating_colour(*request_score),
+			vec![
+				("Level", format!("\"{}\" ({})", level_name, level_id)),
+				("Requested", request_score.to_string()),
+				("Feedback", has_requested_feedback.to_string()),
+				("Notify", notify.to_string()),
+			]
+		),
+		AuditEvent::LevelRequestWithdrawn {
+			level_id,
+			level_name,
+			..
+		} => (
+			"Level request withdrawn",
+			Colour::LIGHT_GREY,
+			vec![("Level", format!("\"{}\" ({})", level_name, level_id))]
+		),
+		AuditEvent::ReviewPosted { level_id, .. } => (
+			"Review posted",
+			Colour::BLUE,
+			vec![("Level ID", level_id.to_string())]
+		),
+		AuditEvent::ReviewEdited { level_id, .. } => (
+			"Review edited",
+			Colour::BLUE,
+			vec![("Level ID", level_id.to_string())]
+		),
+		AuditEvent::LevelSent {
+			level_id,
+			level_name,
+			suggested_score,
+			suggested_rating,
+			..
+		} => (
+			"Level sent",
+			Colour::DARK_GREEN,
+			vec![
+				("Level", format!("\"{}\" ({})", level_name, level_id)),
+				("Suggested score", suggested_score.to_string()),
+				("Suggested rating", suggested_rating.to_string()),
+			]
+		),
+		AuditEvent::LevelNotSent {
+			level_id,
+			level_name,
+			..
+		} => (
+			"Level not sent",
+			Colour::LIGHT_GREY,
+			vec![("Level", format!("\"{}\" ({})", level_name, level_id))]
+		),
+		AuditEvent::ReviewerAdded { reviewer, .. } => (
+			"Reviewer added",
+			Colour::DARK_GREEN,
+			vec![("Reviewer", format_audit_user(reviewer))]
+		),
+		AuditEvent::ReviewerRemoved { reviewer, .. } => (
+			"Reviewer removed",
+			Colour::ORANGE,
+			vec![("Reviewer", format_audit_user(reviewer))]
+		),
+		AuditEvent::PermissionDenied {
+			action,
+			required_permission,
+			..
+		} => (
+			"Permission denied",
+			Colour::ORANGE,
+			vec![
+				("Action", action.clone()),
+				("Required permission", required_permission.to_string()),
+			]
+		),
+		AuditEvent::Error { action, error, .. } => (
+			"Error",
+			Colour::RED,
+			vec![("Action", action.clone()), ("Error", error.clone())]
+		)
+	};
+	let actor = format_audit_user(audit_record.event.actor());
+
+	let mut text = MessageBuilder::new();
+	text.push_bold_line_safe(title);
+	for (name, value) in &fields {
+		text.push_line_safe(format!("{}: {}", name, value));
+	}
+	text.push_line_safe(format!("By {}", actor));
+
+	let embed = CreateEmbed::new()
+		.title(title)
+		.colour(colour)
+		.fields(fields.into_iter().map(|(name, value)| {
+			(
+				name,
+				value.chars().take(MAX_FIELD_LENGTH).collect::<String>(),
+				true
+			)
+		}))
+		.footer(CreateEmbedFooter::new(actor))
+		.timestamp(audit_record.timestamp);
+
+	RenderedMessage {
+		text: text.build(),
+		embed,
+		mention: None,
+		components: Vec::new()
+	}
+}
+
+fn format_audit_user(audit_user: &AuditUser) -> String {
+	format!("{} ({})", audit_user.name, audit_user.id)
+}
+
+fn level_embed(level_request: &LevelRequestData) -> CreateEmbed {
+	CreateEmbed::new()
+		.title(format!(
+			"\"{}\" by {}",
+			level_request.level_name, level_request.level_author
+		))
+		.colour(get_request_rating_colour(level_request.request_score))
+		.footer(CreateEmbedFooter::new(format!(
+			"Level ID: {}",
+			level_request.level_id
+		)))
+}
+
+fn get_reward_name(level_length: LevelLength) -> &'static str {
+	if level_length == LevelLength::Platformer {
+		"Moons"
+	} else {
+		"Stars"
+	}
+}
+
+fn requester_mention(level_request: &LevelRequestData) -> Option<String> {
+	if level_request.notify {
+		Some(UserId::new(level_request.discord_id).mention().to_string())
+	} else {
+		None
+	}
+}
+
+fn get_request_rating_colour(request_rating: RequestRating) -> Colour {
+	match request_rating {
+		RequestRating::One => Colour::new(0x9E9E9E),
+		RequestRating::Two => Colour::new(0x4FC3F7),
+		RequestRating::Three => Colour::new(0x66BB6A),
+		RequestRating::Four | RequestRating::Five => Colour::new(0xFFCA28),
+		RequestRating::Six | RequestRating::Seven => Colour::new(0xFF7043),
+		RequestRating::Eight | RequestRating::Nine => Colour::new(0xEC407A),
+		RequestRating::Ten => Colour::new(0xB71C1C)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use chrono::Utc;
+
+	use crate::{
+		model::{
+			audit_event::{AuditEvent, AuditRecord, AuditUser},
+			moderator::{Moderator, SuggestedRating, SuggestedScore},
+			permission::Permission,
+			request_score::{LevelLength, RequestRating},
+			requestx_api::level_request_data::LevelRequestData
+		},
+		util::render::{
+			render_audit_event, render_level_request, render_level_review, render_send_level
+		}
+	};
+
+	fn test_level_request(level_length: LevelLength, notify: bool) -> LevelRequestData {
+		LevelRequestData {
+			level_id: 97624039,
+			discord_id: 164072941645070336,
+			discord_message_id: Some(1),
+			discord_thread_id: Some(2),
+			discord_channel_id: None,
+			level_name: "Some Level".to_string(),
+			level_author: "Some Author".to_string(),
+			request_score: RequestRating::Four,
+			level_length,
+			youtube_video_link: "https://youtu.be/dQw4w9WgXcQ".to_string(),
+			has_requested_feedback: true,
+			notify,
+			suggested_score: None,
+			suggested_rating: None
+		}
+	}
+
+	#[test]
+	fn send_level_text_should_reward_moons_for_platformers() {
+		let send_level_request = Moderator {
+			level_id: 97624039,
+			suggested_score: SuggestedScore::Four,
+			suggested_rating: SuggestedRating::Feature
+		};
+		let rendered = render_send_level(
+			&test_level_request(LevelLength::Platformer, false),
+			&send_level_request
+		);
+
+		assert_eq!(
+			rendered.text,
+			"\"Some Level\" (97624039) **has **been sent for **Feature, Four Moons!**"
+		);
+		assert_eq!(rendered.mention, None);
+	}
+
+	#[test]
+	fn level_request_should_link_scheme_less_videos_over_https() {
+		let mut level_request = test_level_request(LevelLength::Short, false);
+		level_request.youtube_video_link = "youtu.be/dQw4w9WgXcQ".to_string();
+
+		let rendered = render_level_request(&level_request);
+
+		assert_eq!(
+			serde_json::to_value(&rendered.embed).unwrap()["url"],
+			"https://youtu.be/dQw4w9WgXcQ"
+		);
+		assert!(rendered.text.contains("https://youtu.be/dQw4w9WgXcQ"));
+	}
+
+	#[test]
+	fn level_review_text_should_quote_every_paragraph() {
+		let rendered = render_level_review(
+			&test_level_request(LevelLength::Short, false),
+			"<@1>",
+			"First paragraph\n\nSecond paragraph"
+		);
+
+		assert!(rendered
+			.text
+			.ends_with("> First paragraph\n> \n> Second paragraph\n"));
+	}
+
+	#[test]
+	fn level_review_should_mention_requester_when_notify_is_set() {
+		let rendered = render_level_review(
+			&test_level_request(LevelLength::Short, true),
+			"<@1>",
+			"Nice level"
+		);
+
+		assert_eq!(rendered.mention, Some("<@164072941645070336>".to_string()));
+		assert!(rendered.text.ends_with("<@164072941645070336>\n"));
+	}
+
+	#[test]
+	fn audit_event_text_should_name_the_actor() {
+		let rendered = render_audit_event(&AuditRecord {
+			timestamp: Utc::now(),
+			guild_id: 1,
+			event: AuditEvent::PermissionDenied {
+				user: AuditUser {
+					id: 164072941645070336,
+					name: "someuser".to_string()
+				},
+				action: "send-level".to_string(),
+				required_permission: Permission::Moderator
+			}
+		});
+
+		assert_eq!(
+			rendered.text,
+			"**Permission denied**\nAction: send-level\nRequired permission: Moderator\nBy someuser \
+			 (164072941645070336)\n"
+		);
+	}
+}